@@ -0,0 +1,53 @@
+//! Run with `cargo bench --bench poisson_disc`; compare against a saved run
+//! with `-- --save-baseline before` and `-- --baseline before`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use generustive::geometry::*;
+use rand::{rngs::SmallRng, SeedableRng};
+
+fn square(size: f64) -> BB {
+    (Vec2::xy(0., 0.), Vec2::xy(size, size)).bb()
+}
+
+fn fixed_radius(c: &mut Criterion) {
+    let mut group = c.benchmark_group("poisson_disc");
+    group.sample_size(10);
+    for size in [1_000., 3_000., 10_000.] {
+        group.bench_with_input(BenchmarkId::new("square", size), &size, |b, &size| {
+            b.iter(|| {
+                let mut rng = SmallRng::seed_from_u64(0);
+                poisson_disc(&mut rng, square(size), 5., 20)
+            })
+        });
+    }
+    let circle = Circle::new(Vec2::xy(0., 0.), 1_000.);
+    group.bench_function("circle", |b| {
+        b.iter(|| {
+            let mut rng = SmallRng::seed_from_u64(0);
+            poisson_disc(&mut rng, circle, 5., 20)
+        })
+    });
+    group.finish();
+}
+
+/// The generic samplers, for comparison with the specialized fixed-radius one.
+fn generic(c: &mut Criterion) {
+    let mut group = c.benchmark_group("poisson_disc_generic");
+    group.sample_size(10);
+    group.bench_function("variable", |b| {
+        b.iter(|| {
+            let mut rng = SmallRng::seed_from_u64(0);
+            poisson_disc_variable(&mut rng, square(1_000.), |_| 5., 20)
+        })
+    });
+    group.bench_function("periodic", |b| {
+        b.iter(|| {
+            let mut rng = SmallRng::seed_from_u64(0);
+            poisson_disc_periodic(&mut rng, square(1_000.), 5., 20)
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, fixed_radius, generic);
+criterion_main!(benches);
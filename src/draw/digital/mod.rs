@@ -27,6 +27,104 @@ impl Sketch<Circle> for Context {
     }
 }
 
+impl Sketch<CubicBezier> for Context {
+    fn sketch(&self, curve: CubicBezier) {
+        let CubicBezier {
+            start,
+            control1,
+            control2,
+            end,
+        } = curve;
+
+        self.move_to(start.x, start.y);
+        self.curve_to(control1.x, control1.y, control2.x, control2.y, end.x, end.y);
+    }
+}
+
+impl Sketch<QuadBezier> for Context {
+    fn sketch(&self, curve: QuadBezier) {
+        self.sketch(curve.to_cubic())
+    }
+}
+
+/// Connected path of curve segments, each starting where the previous one ended.
+impl Sketch<&[CubicBezier]> for Context {
+    fn sketch(&self, curves: &[CubicBezier]) {
+        if let Some(first) = curves.first() {
+            self.move_to(first.start.x, first.start.y);
+        }
+        for curve in curves {
+            let CubicBezier {
+                control1,
+                control2,
+                end,
+                ..
+            } = *curve;
+            self.curve_to(control1.x, control1.y, control2.x, control2.y, end.x, end.y);
+        }
+    }
+}
+
+/// Open polyline through the points.
+impl Sketch<&[Vec2]> for Context {
+    fn sketch(&self, points: &[Vec2]) {
+        if let Some((&Vec2 { x, y }, rest)) = points.split_first() {
+            self.move_to(x, y);
+            for &Vec2 { x, y } in rest {
+                self.line_to(x, y);
+            }
+        }
+    }
+}
+
+impl Sketch<&Polygon> for Context {
+    fn sketch(&self, polygon: &Polygon) {
+        let mut points = polygon.points();
+        if let Some(&Vec2 { x, y }) = points.next() {
+            self.move_to(x, y);
+            for &Vec2 { x, y } in points {
+                self.line_to(x, y);
+            }
+            self.close_path();
+        }
+    }
+}
+
+impl Sketch<Triangle> for Context {
+    fn sketch(&self, triangle: Triangle) {
+        let Triangle { a, b, c } = triangle;
+        self.move_to(a.x, a.y);
+        self.line_to(b.x, b.y);
+        self.line_to(c.x, c.y);
+        self.close_path();
+    }
+}
+
+/// Holes are separate subpaths with opposite orientation, so both fill rules work.
+impl Sketch<&MultiPolygon> for Context {
+    fn sketch(&self, multi_polygon: &MultiPolygon) {
+        for ring in multi_polygon.rings() {
+            self.sketch(ring);
+        }
+    }
+}
+
+impl Sketch<BB> for Context {
+    fn sketch(&self, bb: BB) {
+        let (min, max) = (bb.min(), bb.max());
+        self.rectangle(min.x, min.y, max.x - min.x, max.y - min.y);
+    }
+}
+
+/// Outlines of the leaves, to see how the tree is split.
+impl<T> Sketch<&Quadtree<T>> for Context {
+    fn sketch(&self, quadtree: &Quadtree<T>) {
+        for leaf in quadtree.leaves() {
+            self.sketch(leaf);
+        }
+    }
+}
+
 pub trait Scoping {
     fn scoped<R>(&self, body: impl FnOnce(&Context) -> Result<R, Error>) -> Result<R, Error>;
 }
@@ -41,7 +139,8 @@ impl Scoping for Context {
 }
 
 pub mod png {
-    use cairo::{Context, Error, Format, ImageSurface, IoError};
+    use crate::geometry::*;
+    use cairo::{BorrowError, Context, Error, Format, ImageSurface, IoError};
     use std::{fs::File, path::Path};
 
     pub fn write_file<P: AsRef<Path>, R>(
@@ -60,6 +159,131 @@ pub mod png {
         let mut file = File::create(path)?;
         surface.write_to_png(&mut file)
     }
+
+    /// Brightness of an image, one value per pixel from 0 (black) to 1 (white).
+    #[derive(Debug, Clone)]
+    pub struct Grayscale {
+        width: usize,
+        height: usize,
+        values: Vec<f64>,
+    }
+
+    impl Grayscale {
+        pub fn width(&self) -> usize {
+            self.width
+        }
+
+        pub fn height(&self) -> usize {
+            self.height
+        }
+
+        /// Brightness of the pixel containing the point. Outside of the image
+        /// is white.
+        pub fn brightness(&self, point: Vec2) -> f64 {
+            let (x, y) = (point.x.floor(), point.y.floor());
+            if x < 0. || y < 0. || x >= self.width as f64 || y >= self.height as f64 {
+                return 1.;
+            }
+            self.values[y as usize * self.width + x as usize]
+        }
+
+        /// Opposite of brightness, useful as a density for dark ink on white
+        /// paper.
+        pub fn darkness(&self, point: Vec2) -> f64 {
+            1. - self.brightness(point)
+        }
+    }
+
+    impl HasBB for Grayscale {
+        fn bb(&self) -> BB {
+            (
+                Vec2::xy(0., 0.),
+                Vec2::xy(self.width as f64, self.height as f64),
+            )
+                .bb()
+        }
+    }
+
+    /// Read a PNG file as grayscale. Transparent parts count as white.
+    pub fn read_grayscale<P: AsRef<Path>>(path: P) -> Result<Grayscale, IoError> {
+        let mut file = File::open(path)?;
+        let image = ImageSurface::create_from_png(&mut file)?;
+        let (width, height) = (image.width(), image.height());
+
+        // Flatten onto white, so we only have to deal with one pixel format.
+        let surface = ImageSurface::create(Format::ARgb32, width, height)?;
+        {
+            let context = Context::new(&surface)?;
+            context.set_source_rgb(1., 1., 1.);
+            context.paint()?;
+            context.set_source_surface(&image, 0., 0.)?;
+            context.paint()?;
+        }
+        surface.flush();
+
+        let (width, height) = (width as usize, height as usize);
+        let stride = surface.stride() as usize;
+        let mut values = Vec::with_capacity(width * height);
+        surface
+            .with_data(|data| {
+                for row in data.chunks(stride).take(height) {
+                    for pixel in row.chunks_exact(4).take(width) {
+                        let argb = u32::from_ne_bytes([pixel[0], pixel[1], pixel[2], pixel[3]]);
+                        let channel = |shift: u32| ((argb >> shift) & 0xff) as f64 / 255.;
+                        // Rec. 709 luma
+                        values
+                            .push(0.2126 * channel(16) + 0.7152 * channel(8) + 0.0722 * channel(0));
+                    }
+                }
+            })
+            .map_err(|err| match err {
+                BorrowError::Cairo(err) => IoError::Cairo(err),
+                BorrowError::NonExclusive => {
+                    unreachable!("Reading data does not need exclusive access")
+                }
+            })?;
+
+        Ok(Grayscale {
+            width,
+            height,
+            values,
+        })
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use rand::{rngs::SmallRng, SeedableRng};
+
+        #[test]
+        fn read_back_written_file() {
+            let path = std::env::temp_dir().join("generustive-read-grayscale.png");
+            write_file(&path, 20, 10, |width, height, context| {
+                context.set_source_rgb(0., 0., 0.);
+                context.rectangle(0., 0., width / 2., height);
+                context.fill()
+            })
+            .unwrap();
+            let image = read_grayscale(&path).unwrap();
+            assert_eq!((image.width(), image.height()), (20, 10));
+            assert_eq!(image.brightness(Vec2::xy(2.5, 5.)), 0.);
+            // The right half was left transparent.
+            assert_eq!(image.brightness(Vec2::xy(17.5, 5.)), 1.);
+            assert_eq!(image.darkness(Vec2::xy(-1., 5.)), 0.);
+
+            let mut rng = SmallRng::seed_from_u64(0);
+            let dots = stipple(
+                &mut rng,
+                &image,
+                |p| image.darkness(p),
+                20,
+                5,
+                DotSize::Constant(0.5),
+            );
+            assert_eq!(dots.len(), 20);
+            assert!(dots.iter().all(|dot| dot.center.x <= 10.));
+        }
+    }
 }
 
 pub mod svg {
@@ -0,0 +1,648 @@
+use crate::geometry::core::*;
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+/// Boolean operations between two shapes.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum BooleanOp {
+    Union,
+    Intersection,
+    Difference,
+    Xor,
+}
+
+impl BooleanOp {
+    fn apply(self, in_a: bool, in_b: bool) -> bool {
+        match self {
+            BooleanOp::Union => in_a || in_b,
+            BooleanOp::Intersection => in_a && in_b,
+            BooleanOp::Difference => in_a && !in_b,
+            BooleanOp::Xor => in_a != in_b,
+        }
+    }
+}
+
+/// Shapes described by a set of closed rings.
+///
+/// The rings are interpreted using the even-odd rule: a point is inside the
+/// shape if a ray from it crosses the rings an odd number of times. Ring
+/// orientation is therefore irrelevant for the input of boolean operations.
+pub trait Rings {
+    fn rings(&self) -> Vec<&Polygon>;
+}
+
+impl Rings for Polygon {
+    fn rings(&self) -> Vec<&Polygon> {
+        vec![self]
+    }
+}
+
+impl Rings for PolygonWithHoles {
+    fn rings(&self) -> Vec<&Polygon> {
+        PolygonWithHoles::rings(self).collect()
+    }
+}
+
+impl Rings for MultiPolygon {
+    fn rings(&self) -> Vec<&Polygon> {
+        MultiPolygon::rings(self).collect()
+    }
+}
+
+impl<T: Rings> Rings for [T] {
+    fn rings(&self) -> Vec<&Polygon> {
+        self.iter().flat_map(|shape| shape.rings()).collect()
+    }
+}
+
+pub trait BooleanOps: Rings {
+    fn union<Other: Rings + ?Sized>(&self, other: &Other) -> MultiPolygon {
+        boolean(BooleanOp::Union, self, other)
+    }
+
+    fn intersection<Other: Rings + ?Sized>(&self, other: &Other) -> MultiPolygon {
+        boolean(BooleanOp::Intersection, self, other)
+    }
+
+    fn difference<Other: Rings + ?Sized>(&self, other: &Other) -> MultiPolygon {
+        boolean(BooleanOp::Difference, self, other)
+    }
+
+    fn xor<Other: Rings + ?Sized>(&self, other: &Other) -> MultiPolygon {
+        boolean(BooleanOp::Xor, self, other)
+    }
+}
+
+impl<T: Rings + ?Sized> BooleanOps for T {}
+
+/// Combine two shapes.
+///
+/// Works for concave, self-touching and self-intersecting inputs (under the
+/// even-odd rule). The result consists of simple rings: outer boundaries with
+/// positive, holes with negative orientation.
+///
+/// The algorithm splits all edges at their mutual intersections and then
+/// decides for each resulting edge whether it separates the inside from the
+/// outside of the result. This is quadratic in the number of edges, which is
+/// fine for the shapes we usually draw.
+pub fn boolean<A: Rings + ?Sized, B: Rings + ?Sized>(op: BooleanOp, a: &A, b: &B) -> MultiPolygon {
+    combine(&edges(a), &edges(b), |arrangement, p| {
+        op.apply(
+            even_odd(&arrangement.a_pieces, p),
+            even_odd(&arrangement.b_pieces, p),
+        )
+    })
+}
+
+/// Rule to decide which points are inside of a set of possibly overlapping
+/// rings.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum FillRule {
+    /// Inside if a ray crosses the rings an odd number of times.
+    EvenOdd,
+    /// Inside if the rings wind around the point at all.
+    NonZero,
+    /// Inside if the rings wind around the point in positive direction.
+    Positive,
+}
+
+/// Resolve overlaps and self-intersections of a shape into simple rings, as
+/// returned by [`boolean`].
+pub fn resolve<Shape: Rings + ?Sized>(shape: &Shape, rule: FillRule) -> MultiPolygon {
+    combine(&edges(shape), &[], |arrangement, p| match rule {
+        FillRule::EvenOdd => even_odd(&arrangement.a_pieces, p),
+        FillRule::NonZero => winding_number(&arrangement.a_pieces, p) != 0,
+        FillRule::Positive => winding_number(&arrangement.a_pieces, p) > 0,
+    })
+}
+
+fn edges<Shape: Rings + ?Sized>(shape: &Shape) -> Vec<Line> {
+    shape
+        .rings()
+        .into_iter()
+        .flat_map(|ring| ring.edges())
+        .collect()
+}
+
+fn combine(
+    a_edges: &[Line],
+    b_edges: &[Line],
+    inside: impl Fn(&Arrangement, Vec2) -> bool,
+) -> MultiPolygon {
+    if a_edges.is_empty() && b_edges.is_empty() {
+        return MultiPolygon::default();
+    }
+    let bb: BB = a_edges
+        .iter()
+        .chain(b_edges.iter())
+        .map(|edge| edge.bb())
+        .sum();
+    let extent = (bb.max() - bb.min()).norm().max(1.);
+    let eps = extent * 1e-10;
+
+    let arrangement = Arrangement::new(a_edges, b_edges, eps);
+    let boundary = arrangement.boundary(|p| inside(&arrangement, p));
+    let rings = link_rings(&arrangement.vertices, &boundary);
+    assemble(rings, eps)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Source {
+    A,
+    B,
+}
+
+/// All input edges, split at their mutual intersections. Coincident pieces are
+/// merged, so two distinct segments only ever touch at their end points.
+struct Arrangement {
+    vertices: Vec<Vec2>,
+    segments: Vec<(usize, usize)>,
+    a_pieces: Vec<Line>,
+    b_pieces: Vec<Line>,
+}
+
+impl Arrangement {
+    fn new(a_edges: &[Line], b_edges: &[Line], eps: f64) -> Self {
+        let edges: Vec<(Line, Source)> = a_edges
+            .iter()
+            .map(|&edge| (edge, Source::A))
+            .chain(b_edges.iter().map(|&edge| (edge, Source::B)))
+            .collect();
+
+        let mut splits: Vec<Vec<f64>> = vec![vec![0., 1.]; edges.len()];
+        let bbs: Vec<BB> = edges.iter().map(|(edge, _)| edge.bb()).collect();
+        for i in 0 .. edges.len() {
+            for j in i + 1 .. edges.len() {
+                if !bbs_touch(bbs[i], bbs[j], eps) {
+                    continue;
+                }
+                match edges[i].0.intersect_with_tolerance(edges[j].0, eps) {
+                    Intersection::None => {}
+                    Intersection::Point { t, u, .. } => {
+                        splits[i].push(t);
+                        splits[j].push(u);
+                    }
+                    Intersection::Overlap { t, u, .. } => {
+                        splits[i].extend([t.0, t.1]);
+                        splits[j].extend([u.0, u.1]);
+                    }
+                }
+            }
+        }
+
+        let mut pool = VertexPool::new(eps);
+        let mut segments = Vec::new();
+        let mut segment_index = HashMap::new();
+        let mut a_pieces = Vec::new();
+        let mut b_pieces = Vec::new();
+        for ((edge, source), mut ts) in edges.into_iter().zip(splits) {
+            ts.sort_by(|t, u| t.total_cmp(u));
+            let ids: Vec<usize> = ts.into_iter().map(|t| pool.id(edge.at(t))).collect();
+            for piece in ids.windows(2) {
+                let (from, to) = (piece[0], piece[1]);
+                if from == to {
+                    continue;
+                }
+                let line = Line::from_to(pool.vertices[from], pool.vertices[to]);
+                match source {
+                    Source::A => a_pieces.push(line),
+                    Source::B => b_pieces.push(line),
+                }
+                let key = (from.min(to), from.max(to));
+                segment_index.entry(key).or_insert_with(|| {
+                    segments.push(key);
+                    segments.len() - 1
+                });
+            }
+        }
+
+        Arrangement {
+            vertices: pool.vertices,
+            segments,
+            a_pieces,
+            b_pieces,
+        }
+    }
+
+    fn line(&self, (from, to): (usize, usize)) -> Line {
+        Line::from_to(self.vertices[from], self.vertices[to])
+    }
+
+    /// Directed boundary edges of the result, with the inside on their left.
+    fn boundary(&self, inside: impl Fn(Vec2) -> bool) -> Vec<(usize, usize)> {
+        let mut result = Vec::new();
+        for (i, &segment) in self.segments.iter().enumerate() {
+            let line = self.line(segment);
+            let length = line.length();
+            let midpoint = line.at(0.5);
+
+            // Probe both sides of the segment, but stay closer to it than to
+            // any other segment so the probes are in the two adjacent faces.
+            let clearance = self
+                .segments
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .map(|(_, &other)| self.line(other).distance_to(midpoint))
+                .fold(length, f64::min);
+            let offset = line.vec2().perpendicular() / length * (clearance / 2.);
+            let left = midpoint + offset;
+            let right = midpoint - offset;
+
+            match (inside(left), inside(right)) {
+                (true, false) => result.push(segment),
+                (false, true) => result.push((segment.1, segment.0)),
+                _ => {}
+            }
+        }
+        result
+    }
+}
+
+fn bbs_touch(a: BB, b: BB, eps: f64) -> bool {
+    a.min().x <= b.max().x + eps
+        && b.min().x <= a.max().x + eps
+        && a.min().y <= b.max().y + eps
+        && b.min().y <= a.max().y + eps
+}
+
+/// Even-odd rule point containment.
+fn even_odd(edges: &[Line], p: Vec2) -> bool {
+    let mut inside = false;
+    for &Line { start: a, end: b } in edges {
+        if (a.y > p.y) != (b.y > p.y) {
+            let x = a.x + (p.y - a.y) / (b.y - a.y) * (b.x - a.x);
+            if p.x < x {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+/// Number of times the edges wind around the point, counting positive
+/// orientation as positive.
+fn winding_number(edges: &[Line], p: Vec2) -> i32 {
+    let mut winding = 0;
+    for &Line { start: a, end: b } in edges {
+        let side = (b - a).cross(p - a);
+        if a.y <= p.y && b.y > p.y && side > 0. {
+            winding += 1;
+        } else if b.y <= p.y && a.y > p.y && side < 0. {
+            winding -= 1;
+        }
+    }
+    winding
+}
+
+/// Deduplicates points that are closer than the tolerance.
+struct VertexPool {
+    eps: f64,
+    vertices: Vec<Vec2>,
+    lookup: HashMap<(i64, i64), usize>,
+}
+
+impl VertexPool {
+    fn new(eps: f64) -> Self {
+        VertexPool {
+            eps,
+            vertices: Vec::new(),
+            lookup: HashMap::new(),
+        }
+    }
+
+    fn id(&mut self, p: Vec2) -> usize {
+        let key = (
+            (p.x / self.eps).round() as i64,
+            (p.y / self.eps).round() as i64,
+        );
+        for dx in -1 ..= 1 {
+            for dy in -1 ..= 1 {
+                if let Some(&id) = self.lookup.get(&(key.0 + dx, key.1 + dy)) {
+                    if (self.vertices[id] - p).norm() <= 2. * self.eps {
+                        return id;
+                    }
+                }
+            }
+        }
+        self.vertices.push(p);
+        let id = self.vertices.len() - 1;
+        self.lookup.insert(key, id);
+        id
+    }
+}
+
+/// Chain directed edges into closed rings.
+///
+/// At vertices with several outgoing edges we take the sharpest turn to the
+/// right, so that each ring traces a single face. Rings that still touch
+/// themselves are split into simple loops.
+fn link_rings(vertices: &[Vec2], edges: &[(usize, usize)]) -> Vec<Vec<Vec2>> {
+    let mut outgoing: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (i, &(from, _)) in edges.iter().enumerate() {
+        outgoing.entry(from).or_default().push(i);
+    }
+
+    let mut used = vec![false; edges.len()];
+    let mut rings = Vec::new();
+    for first in 0 .. edges.len() {
+        if used[first] {
+            continue;
+        }
+        let start = edges[first].0;
+        let mut walk = vec![start];
+        let mut current = first;
+        loop {
+            used[current] = true;
+            let (from, to) = edges[current];
+            if to == start {
+                break;
+            }
+            walk.push(to);
+
+            let back = vertices[from] - vertices[to];
+            let next = outgoing
+                .get(&to)
+                .into_iter()
+                .flatten()
+                .copied()
+                .filter(|&candidate| !used[candidate])
+                .min_by(|&e1, &e2| {
+                    let turn =
+                        |e: usize| clockwise_angle(back, vertices[edges[e].1] - vertices[to]);
+                    turn(e1).total_cmp(&turn(e2))
+                });
+            match next {
+                Some(next) => current = next,
+                None => break, // Dangling edge, can only happen with degenerate input.
+            }
+        }
+        rings.extend(split_at_repeated_vertices(walk));
+    }
+
+    rings
+        .into_iter()
+        .filter(|ring| ring.len() >= 3)
+        .map(|ring| ring.into_iter().map(|i| vertices[i]).collect())
+        .collect()
+}
+
+/// Angle to rotate `from` clockwise until it points in direction `to`, in (0, 2π].
+fn clockwise_angle(from: Vec2, to: Vec2) -> f64 {
+    let ccw = from.cross(to).atan2(from.dot(to));
+    let cw = -ccw;
+    if cw <= 0. {
+        cw + 2. * PI
+    } else {
+        cw
+    }
+}
+
+fn split_at_repeated_vertices(walk: Vec<usize>) -> Vec<Vec<usize>> {
+    let mut loops = Vec::new();
+    let mut stack: Vec<usize> = Vec::with_capacity(walk.len());
+    let mut position: HashMap<usize, usize> = HashMap::new();
+    for v in walk {
+        if let Some(&i) = position.get(&v) {
+            let closed: Vec<usize> = stack.drain(i + 1 ..).collect();
+            for w in &closed {
+                position.remove(w);
+            }
+            let mut ring = vec![v];
+            ring.extend(closed);
+            loops.push(ring);
+        } else {
+            position.insert(v, stack.len());
+            stack.push(v);
+        }
+    }
+    loops.push(stack);
+    loops
+}
+
+/// Remove vertices on straight edges, and sort rings into outer boundaries
+/// and holes.
+fn assemble(rings: Vec<Vec<Vec2>>, eps: f64) -> MultiPolygon {
+    let mut outers = Vec::new();
+    let mut holes = Vec::new();
+    for ring in rings {
+        let ring = remove_collinear(ring, eps);
+        if ring.len() < 3 {
+            continue;
+        }
+        let polygon = Polygon::from_points(ring);
+        if polygon.area() <= eps * eps {
+            continue;
+        }
+        match polygon.orientation() {
+            Orientation::Positive => outers.push(polygon),
+            Orientation::Negative => holes.push(polygon),
+        }
+    }
+
+    outers.sort_by(|p, q| p.area().total_cmp(&q.area()));
+    let mut parts: Vec<PolygonWithHoles> = outers.into_iter().map(PolygonWithHoles::from).collect();
+    for hole in holes {
+        // Ring edges never overlap with other rings, so the midpoint of a hole
+        // edge is strictly inside or outside of every outer boundary.
+        let probe = match hole.edges().next() {
+            Some(edge) => edge.at(0.5),
+            None => continue,
+        };
+        let smallest_container = parts.iter_mut().find(|part| {
+            let outer_edges: Vec<Line> = part.outer.edges().collect();
+            even_odd(&outer_edges, probe)
+        });
+        if let Some(part) = smallest_container {
+            part.holes.push(hole);
+        }
+    }
+    MultiPolygon::from_parts(parts)
+}
+
+fn remove_collinear(mut ring: Vec<Vec2>, eps: f64) -> Vec<Vec2> {
+    let mut changed = true;
+    while changed && ring.len() >= 3 {
+        changed = false;
+        let mut i = 0;
+        while i < ring.len() && ring.len() >= 3 {
+            let n = ring.len();
+            let prev = ring[(i + n - 1) % n];
+            let here = ring[i];
+            let next = ring[(i + 1) % n];
+            let (a, b) = (here - prev, next - here);
+            let straight = a.cross(b).abs() <= eps * (a.norm() + b.norm()) && a.dot(b) > 0.;
+            if straight {
+                ring.remove(i);
+                changed = true;
+            } else {
+                i += 1;
+            }
+        }
+    }
+    ring
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::*;
+
+    fn square(x: f64, y: f64, size: f64) -> Polygon {
+        Polygon::from_points(vec![
+            Vec2::xy(x, y),
+            Vec2::xy(x + size, y),
+            Vec2::xy(x + size, y + size),
+            Vec2::xy(x, y + size),
+        ])
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-6, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn overlapping_squares() {
+        let a = square(0., 0., 10.);
+        let b = square(5., 5., 10.);
+
+        let union = a.union(&b);
+        assert_eq!(union.len(), 1);
+        assert_close(union.area(), 175.);
+        assert_eq!(union.parts().next().unwrap().outer.points().count(), 8);
+
+        assert_close(a.intersection(&b).area(), 25.);
+        assert_close(a.difference(&b).area(), 75.);
+
+        let xor = a.xor(&b);
+        assert_eq!(xor.len(), 2);
+        assert_close(xor.area(), 150.);
+    }
+
+    #[test]
+    fn cut_out_window() {
+        let wall = square(0., 0., 10.);
+        let window = square(3., 3., 4.);
+        let result = wall.difference(&window);
+        assert_eq!(result.len(), 1);
+        let part = result.parts().next().unwrap();
+        assert_eq!(part.holes.len(), 1);
+        assert_eq!(part.outer.orientation(), Orientation::Positive);
+        assert_eq!(part.holes[0].orientation(), Orientation::Negative);
+        assert_close(result.area(), 84.);
+    }
+
+    #[test]
+    fn identical_squares() {
+        let a = square(0., 0., 10.);
+        let b = square(0., 0., 10.);
+        assert_close(a.union(&b).area(), 100.);
+        assert_close(a.intersection(&b).area(), 100.);
+        assert!(a.difference(&b).is_empty());
+    }
+
+    #[test]
+    fn adjacent_squares_merge() {
+        let union = square(0., 0., 10.).union(&square(10., 0., 10.));
+        assert_eq!(union.len(), 1);
+        assert_eq!(union.parts().next().unwrap().outer.points().count(), 4);
+        assert_close(union.area(), 200.);
+    }
+
+    #[test]
+    fn partially_shared_edges() {
+        // The right edge of the first square overlaps the left edge of the
+        // second one only in part, and contains the left edge of the third.
+        let a = square(0., 0., 10.);
+        for (b, area) in [(square(10., 5., 10.), 200.), (square(10., 2., 4.), 116.)] {
+            let union = a.union(&b);
+            assert_eq!(union.len(), 1);
+            assert_close(union.area(), area);
+            assert_close(a.intersection(&b).area(), 0.);
+            assert_close(a.difference(&b).area(), 100.);
+            assert_close(a.xor(&b).area(), area);
+        }
+        // Nearly parallel, overlapping edges.
+        let tilted = Polygon::from_points(vec![
+            Vec2::xy(10., 3.),
+            Vec2::xy(20., 3.),
+            Vec2::xy(20., 13.),
+            Vec2::xy(10. + 1e-12, 13.),
+        ]);
+        let union = a.union(&tilted);
+        assert_eq!(union.len(), 1);
+        assert_close(union.area(), 200.);
+    }
+
+    #[test]
+    fn corner_touching_squares_stay_separate() {
+        let union = square(0., 0., 10.).union(&square(10., 10., 10.));
+        assert_eq!(union.len(), 2);
+        assert_close(union.area(), 200.);
+    }
+
+    #[test]
+    fn concave_and_reversed_input() {
+        // U shape, given in negative orientation.
+        let u = Polygon::from_points(vec![
+            Vec2::xy(0., 0.),
+            Vec2::xy(0., 30.),
+            Vec2::xy(10., 30.),
+            Vec2::xy(10., 10.),
+            Vec2::xy(20., 10.),
+            Vec2::xy(20., 30.),
+            Vec2::xy(30., 30.),
+            Vec2::xy(30., 0.),
+        ]);
+        let bar = Polygon::from_points(vec![
+            Vec2::xy(-5., 20.),
+            Vec2::xy(35., 20.),
+            Vec2::xy(35., 25.),
+            Vec2::xy(-5., 25.),
+        ]);
+        let intersection = u.intersection(&bar);
+        assert_eq!(intersection.len(), 2);
+        assert_close(intersection.area(), 100.);
+
+        let union = u.union(&bar);
+        assert_eq!(union.len(), 1);
+        assert_eq!(union.parts().next().unwrap().holes.len(), 1);
+        assert_close(union.area(), 700. + 200. - 100.);
+    }
+
+    #[test]
+    fn resolve_self_intersecting() {
+        // Square traversed twice in positive direction, plus a square in the
+        // opposite direction overlapping one corner.
+        let twice = Polygon::from_points(vec![
+            Vec2::xy(0., 0.),
+            Vec2::xy(10., 0.),
+            Vec2::xy(10., 10.),
+            Vec2::xy(0., 10.),
+            Vec2::xy(0., 0.),
+            Vec2::xy(10., 0.),
+            Vec2::xy(10., 10.),
+            Vec2::xy(0., 10.),
+        ]);
+        let negative = Polygon::from_points(vec![
+            Vec2::xy(5., 5.),
+            Vec2::xy(5., 15.),
+            Vec2::xy(15., 15.),
+            Vec2::xy(15., 5.),
+        ]);
+        let shape = vec![twice, negative];
+        let even_odd = resolve(shape.as_slice(), FillRule::EvenOdd);
+        assert_close(even_odd.area(), 100.);
+        assert_eq!(even_odd.bb().min(), Vec2::xy(5., 5.));
+        assert_close(resolve(shape.as_slice(), FillRule::NonZero).area(), 175.);
+        let positive = resolve(shape.as_slice(), FillRule::Positive);
+        assert_close(positive.area(), 100.);
+        assert_eq!(positive.bb().min(), Vec2::xy(0., 0.));
+    }
+
+    #[test]
+    fn operations_compose() {
+        let frame = square(0., 0., 10.).difference(&square(2., 2., 6.));
+        let result = frame.intersection(&square(5., -5., 20.));
+        assert_close(result.area(), 50. - 18.);
+    }
+}
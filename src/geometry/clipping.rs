@@ -0,0 +1,190 @@
+use crate::geometry::boolean::Rings;
+use crate::geometry::core::*;
+
+/// Which part of a clipped line to keep.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ClipMode {
+    Inside,
+    Outside,
+}
+
+/// Distance below which points count as touching when clipping within the
+/// bounding box.
+pub(crate) fn tolerance(bb: BB) -> f64 {
+    (bb.max() - bb.min()).norm().max(1.) * 1e-10
+}
+
+/// Clip a line against a region, keeping the pieces inside or outside of it.
+///
+/// The region may be concave, and may have holes (even-odd rule, see
+/// [`Rings`]). Pieces running exactly along the region’s boundary may end up
+/// on either side.
+pub fn clip_line<Region: Rings + ?Sized>(line: Line, region: &Region, mode: ClipMode) -> Vec<Line> {
+    let rings = region.rings();
+    let length = line.length();
+    if length == 0. {
+        return Vec::new();
+    }
+    let bb: BB = rings.iter().map(|ring| ring.bb()).sum::<BB>() + line.bb();
+    let eps = tolerance(bb);
+
+    let mut ts = vec![0., 1.];
+    for ring in &rings {
+        for edge in ring.edges() {
+            match line.intersect_with_tolerance(edge, eps) {
+                Intersection::None => {}
+                Intersection::Point { t, .. } => ts.push(t),
+                Intersection::Overlap { t, .. } => ts.extend([t.0, t.1]),
+            }
+        }
+    }
+    ts.sort_by(|t, u| t.total_cmp(u));
+    ts.dedup_by(|t, u| (*t - *u) * length <= eps);
+
+    let keep = |p: Vec2| {
+        let inside = rings.iter().filter(|ring| ring.contains(p)).count() % 2 == 1;
+        match mode {
+            ClipMode::Inside => inside,
+            ClipMode::Outside => !inside,
+        }
+    };
+
+    let mut result: Vec<Line> = Vec::new();
+    for interval in ts.windows(2) {
+        let (t0, t1) = (interval[0], interval[1]);
+        if !keep(line.at((t0 + t1) / 2.)) {
+            continue;
+        }
+        match result.last_mut() {
+            // Touching a corner does not split the line.
+            Some(previous) if previous.end == line.at(t0) => previous.end = line.at(t1),
+            _ => result.push(Line::from_to(line.at(t0), line.at(t1))),
+        }
+    }
+    result
+}
+
+/// Clip a number of lines, see [`clip_line`].
+pub fn clip_lines<Region: Rings + ?Sized>(
+    lines: impl IntoIterator<Item = Line>,
+    region: &Region,
+    mode: ClipMode,
+) -> Vec<Line> {
+    lines
+        .into_iter()
+        .flat_map(|line| clip_line(line, region, mode))
+        .collect()
+}
+
+/// Clip an open polyline, given by its points. Consecutive surviving pieces
+/// stay connected, so the result is a list of polylines.
+pub fn clip_polyline<Region: Rings + ?Sized>(
+    points: &[Vec2],
+    region: &Region,
+    mode: ClipMode,
+) -> Vec<Vec<Vec2>> {
+    let mut result: Vec<Vec<Vec2>> = Vec::new();
+    for segment in points.windows(2) {
+        for piece in clip_line(Line::from_to(segment[0], segment[1]), region, mode) {
+            match result.last_mut() {
+                Some(polyline) if polyline.last() == Some(&piece.start) => polyline.push(piece.end),
+                _ => result.push(vec![piece.start, piece.end]),
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::*;
+
+    fn total_length(lines: &[Line]) -> f64 {
+        lines.iter().map(|line| line.length()).sum()
+    }
+
+    /// Concave U shape, 30 wide, with a 10 wide notch from the top down to y = 10.
+    fn u_shape() -> Polygon {
+        Polygon::from_points(vec![
+            Vec2::xy(0., 0.),
+            Vec2::xy(30., 0.),
+            Vec2::xy(30., 30.),
+            Vec2::xy(20., 30.),
+            Vec2::xy(20., 10.),
+            Vec2::xy(10., 10.),
+            Vec2::xy(10., 30.),
+            Vec2::xy(0., 30.),
+        ])
+    }
+
+    #[test]
+    fn inside_concave() {
+        let line = Line::from_to(Vec2::xy(-10., 20.), Vec2::xy(40., 20.));
+        let inside = clip_line(line, &u_shape(), ClipMode::Inside);
+        assert_eq!(
+            inside,
+            vec![
+                Line::from_to(Vec2::xy(0., 20.), Vec2::xy(10., 20.)),
+                Line::from_to(Vec2::xy(20., 20.), Vec2::xy(30., 20.)),
+            ]
+        );
+    }
+
+    #[test]
+    fn outside_concave() {
+        let line = Line::from_to(Vec2::xy(-10., 20.), Vec2::xy(40., 20.));
+        let outside = clip_line(line, &u_shape(), ClipMode::Outside);
+        assert_eq!(outside.len(), 3);
+        assert!((total_length(&outside) - 30.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn through_vertex_stays_connected() {
+        // Passes through the corner (10, 10) without leaving the polygon.
+        let line = Line::from_to(Vec2::xy(5., 5.), Vec2::xy(15., 15.));
+        let inside = clip_line(line, &u_shape(), ClipMode::Inside);
+        assert_eq!(inside.len(), 1);
+        assert_eq!(inside[0].start, Vec2::xy(5., 5.));
+        assert!((inside[0].end - Vec2::xy(10., 10.)).norm() < 1e-9);
+    }
+
+    #[test]
+    fn with_hole() {
+        let frame = Polygon::from_points(vec![
+            Vec2::xy(0., 0.),
+            Vec2::xy(10., 0.),
+            Vec2::xy(10., 10.),
+            Vec2::xy(0., 10.),
+        ])
+        .difference(&Polygon::from_points(vec![
+            Vec2::xy(2., 2.),
+            Vec2::xy(8., 2.),
+            Vec2::xy(8., 8.),
+            Vec2::xy(2., 8.),
+        ]));
+        let line = Line::from_to(Vec2::xy(-1., 5.), Vec2::xy(11., 5.));
+        let inside = clip_line(line, &frame, ClipMode::Inside);
+        assert_eq!(inside.len(), 2);
+        assert!((total_length(&inside) - 4.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn polyline_stays_connected() {
+        let polyline = vec![
+            Vec2::xy(5., -5.),
+            Vec2::xy(5., 5.),
+            Vec2::xy(25., 5.),
+            Vec2::xy(25., 40.),
+        ];
+        let pieces = clip_polyline(&polyline, &u_shape(), ClipMode::Inside);
+        assert_eq!(
+            pieces,
+            vec![vec![
+                Vec2::xy(5., 0.),
+                Vec2::xy(5., 5.),
+                Vec2::xy(25., 5.),
+                Vec2::xy(25., 30.),
+            ]]
+        );
+    }
+}
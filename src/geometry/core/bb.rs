@@ -1,7 +1,6 @@
 use super::line::*;
 use super::vec2::*;
 use impl_trait_for_tuples::impl_for_tuples;
-use std::f64::INFINITY;
 use std::iter::Sum;
 use std::ops::Add;
 
@@ -13,12 +12,12 @@ pub struct BB {
 
 static ZERO_BB: BB = BB {
     min: Vec2 {
-        x: INFINITY,
-        y: INFINITY,
+        x: f64::INFINITY,
+        y: f64::INFINITY,
     },
     max: Vec2 {
-        x: -INFINITY,
-        y: -INFINITY,
+        x: -f64::INFINITY,
+        y: -f64::INFINITY,
     },
 };
 
@@ -48,6 +47,14 @@ impl BB {
         self + other == other
     }
 
+    /// Whether the boxes share at least one point; touching edges count.
+    pub fn overlaps(self, other: BB) -> bool {
+        self.min.x <= other.max.x
+            && other.min.x <= self.max.x
+            && self.min.y <= other.max.y
+            && other.min.y <= self.max.y
+    }
+
     pub fn max(self) -> Vec2 {
         self.max
     }
@@ -71,6 +78,18 @@ pub trait HasBB {
     fn bb(&self) -> BB;
 }
 
+impl<T: HasBB + ?Sized> HasBB for &T {
+    fn bb(&self) -> BB {
+        (**self).bb()
+    }
+}
+
+impl HasBB for BB {
+    fn bb(&self) -> BB {
+        *self
+    }
+}
+
 impl HasBB for Vec2 {
     fn bb(&self) -> BB {
         BB {
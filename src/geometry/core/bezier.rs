@@ -0,0 +1,394 @@
+use super::bb::*;
+use super::vec2::*;
+
+/// Quadratic Bézier curve.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct QuadBezier {
+    pub start: Vec2,
+    pub control: Vec2,
+    pub end: Vec2,
+}
+
+/// Cubic Bézier curve.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct CubicBezier {
+    pub start: Vec2,
+    pub control1: Vec2,
+    pub control2: Vec2,
+    pub end: Vec2,
+}
+
+impl QuadBezier {
+    pub fn new(start: Vec2, control: Vec2, end: Vec2) -> Self {
+        Self {
+            start,
+            control,
+            end,
+        }
+    }
+
+    pub fn at(self, t: f64) -> Vec2 {
+        let s = 1. - t;
+        self.start * (s * s) + self.control * (2. * s * t) + self.end * (t * t)
+    }
+
+    pub fn derivative(self, t: f64) -> Vec2 {
+        (self.control - self.start) * (2. * (1. - t)) + (self.end - self.control) * (2. * t)
+    }
+
+    /// The second derivative of a quadratic curve is constant.
+    pub fn second_derivative(self) -> Vec2 {
+        (self.end - self.control * 2. + self.start) * 2.
+    }
+
+    /// Split into the parts before and after t (de Casteljau).
+    pub fn split(self, t: f64) -> (Self, Self) {
+        let a = lerp(self.start, self.control, t);
+        let b = lerp(self.control, self.end, t);
+        let mid = lerp(a, b, t);
+        (Self::new(self.start, a, mid), Self::new(mid, b, self.end))
+    }
+
+    /// Exact representation as a cubic curve.
+    pub fn to_cubic(self) -> CubicBezier {
+        CubicBezier::new(
+            self.start,
+            self.start + (self.control - self.start) * (2. / 3.),
+            self.end + (self.control - self.end) * (2. / 3.),
+            self.end,
+        )
+    }
+
+    pub fn length(self) -> f64 {
+        arc_length(|t| self.derivative(t))
+    }
+
+    /// Approximate the curve by a polyline that deviates by at most
+    /// `tolerance` from it, using fewer points in flat regions.
+    pub fn flatten(self, tolerance: f64) -> Vec<Vec2> {
+        self.to_cubic().flatten(tolerance)
+    }
+}
+
+impl CubicBezier {
+    pub fn new(start: Vec2, control1: Vec2, control2: Vec2, end: Vec2) -> Self {
+        Self {
+            start,
+            control1,
+            control2,
+            end,
+        }
+    }
+
+    pub fn at(self, t: f64) -> Vec2 {
+        let s = 1. - t;
+        self.start * (s * s * s)
+            + self.control1 * (3. * s * s * t)
+            + self.control2 * (3. * s * t * t)
+            + self.end * (t * t * t)
+    }
+
+    pub fn derivative(self, t: f64) -> Vec2 {
+        let s = 1. - t;
+        (self.control1 - self.start) * (3. * s * s)
+            + (self.control2 - self.control1) * (6. * s * t)
+            + (self.end - self.control2) * (3. * t * t)
+    }
+
+    pub fn second_derivative(self, t: f64) -> Vec2 {
+        (self.control2 - self.control1 * 2. + self.start) * (6. * (1. - t))
+            + (self.end - self.control2 * 2. + self.control1) * (6. * t)
+    }
+
+    /// Split into the parts before and after t (de Casteljau).
+    pub fn split(self, t: f64) -> (Self, Self) {
+        let a = lerp(self.start, self.control1, t);
+        let b = lerp(self.control1, self.control2, t);
+        let c = lerp(self.control2, self.end, t);
+        let ab = lerp(a, b, t);
+        let bc = lerp(b, c, t);
+        let mid = lerp(ab, bc, t);
+        (
+            Self::new(self.start, a, ab, mid),
+            Self::new(mid, bc, c, self.end),
+        )
+    }
+
+    pub fn length(self) -> f64 {
+        arc_length(|t| self.derivative(t))
+    }
+
+    /// Approximate the curve by a polyline that deviates by at most
+    /// `tolerance` from it, using fewer points in flat regions.
+    pub fn flatten(self, tolerance: f64) -> Vec<Vec2> {
+        let mut result = vec![self.start];
+        self.flatten_into(tolerance.abs(), 0, &mut result);
+        result
+    }
+
+    fn flatten_into(self, tolerance: f64, depth: usize, result: &mut Vec<Vec2>) {
+        // The curve lies within the convex hull of its control points, so
+        // if the controls are close to the chord segment, so is the curve.
+        let flat = distance_to_chord(self.control1, self.start, self.end) <= tolerance
+            && distance_to_chord(self.control2, self.start, self.end) <= tolerance;
+        if flat || depth >= 24 {
+            result.push(self.end);
+        } else {
+            let (left, right) = self.split(0.5);
+            left.flatten_into(tolerance, depth + 1, result);
+            right.flatten_into(tolerance, depth + 1, result);
+        }
+    }
+}
+
+fn lerp(a: Vec2, b: Vec2, t: f64) -> Vec2 {
+    a + (b - a) * t
+}
+
+/// Distance to the segment between start and end. Not to the infinite line,
+/// since controls beyond the ends make the curve overshoot.
+fn distance_to_chord(p: Vec2, start: Vec2, end: Vec2) -> f64 {
+    let chord = end - start;
+    let length_square = chord.norm_square();
+    if length_square == 0. {
+        (p - start).norm()
+    } else {
+        let t = ((p - start).dot(chord) / length_square).clamp(0., 1.);
+        (p - (start + chord * t)).norm()
+    }
+}
+
+/// Integrate the speed |B'(t)| over [0, 1], bisecting until Gauss–Legendre
+/// quadrature agrees with itself on the two halves.
+fn arc_length(derivative: impl Fn(f64) -> Vec2) -> f64 {
+    fn gauss(derivative: &impl Fn(f64) -> Vec2, a: f64, b: f64) -> f64 {
+        // 5-point Gauss–Legendre nodes and weights on [-1, 1].
+        const NODES: [(f64, f64); 5] = [
+            (0., 0.568_888_888_888_888_9),
+            (-0.538_469_310_105_683, 0.478_628_670_499_366_5),
+            (0.538_469_310_105_683, 0.478_628_670_499_366_5),
+            (-0.906_179_845_938_664, 0.236_926_885_056_189_1),
+            (0.906_179_845_938_664, 0.236_926_885_056_189_1),
+        ];
+        let half = (b - a) / 2.;
+        let mid = (a + b) / 2.;
+        NODES
+            .iter()
+            .map(|&(x, w)| w * derivative(mid + half * x).norm())
+            .sum::<f64>()
+            * half
+    }
+
+    fn adaptive(
+        derivative: &impl Fn(f64) -> Vec2,
+        a: f64,
+        b: f64,
+        whole: f64,
+        depth: usize,
+    ) -> f64 {
+        let mid = (a + b) / 2.;
+        let left = gauss(derivative, a, mid);
+        let right = gauss(derivative, mid, b);
+        if depth >= 16 || (left + right - whole).abs() <= 1e-9 * whole.max(1.) {
+            left + right
+        } else {
+            adaptive(derivative, a, mid, left, depth + 1)
+                + adaptive(derivative, mid, b, right, depth + 1)
+        }
+    }
+
+    let whole = gauss(&derivative, 0., 1.);
+    adaptive(&derivative, 0., 1., whole, 0)
+}
+
+/// Parameters in (0, 1) where a·t² + b·t + c vanishes.
+fn roots_in_unit_interval(a: f64, b: f64, c: f64) -> Vec<f64> {
+    let roots = if a.abs() < 1e-12 {
+        if b.abs() < 1e-12 {
+            vec![]
+        } else {
+            vec![-c / b]
+        }
+    } else {
+        let discriminant = b * b - 4. * a * c;
+        if discriminant < 0. {
+            vec![]
+        } else {
+            let sqrt = discriminant.sqrt();
+            vec![(-b + sqrt) / (2. * a), (-b - sqrt) / (2. * a)]
+        }
+    };
+    roots.into_iter().filter(|t| *t > 0. && *t < 1.).collect()
+}
+
+impl HasBB for QuadBezier {
+    fn bb(&self) -> BB {
+        self.to_cubic().bb()
+    }
+}
+
+/// Tight bounding box, including the curve’s extremal points, but not
+/// necessarily the control points.
+impl HasBB for CubicBezier {
+    fn bb(&self) -> BB {
+        let coefficients = |p0: f64, p1: f64, p2: f64, p3: f64| {
+            (
+                3. * (-p0 + 3. * p1 - 3. * p2 + p3),
+                6. * (p0 - 2. * p1 + p2),
+                3. * (p1 - p0),
+            )
+        };
+        let (ax, bx, cx) = coefficients(self.start.x, self.control1.x, self.control2.x, self.end.x);
+        let (ay, by, cy) = coefficients(self.start.y, self.control1.y, self.control2.y, self.end.y);
+        roots_in_unit_interval(ax, bx, cx)
+            .into_iter()
+            .chain(roots_in_unit_interval(ay, by, cy))
+            .map(|t| self.at(t).bb())
+            .sum::<BB>()
+            + self.start.bb()
+            + self.end.bb()
+    }
+}
+
+/// Start, control, end
+impl From<(Vec2, Vec2, Vec2)> for QuadBezier {
+    fn from(points: (Vec2, Vec2, Vec2)) -> Self {
+        Self::new(points.0, points.1, points.2)
+    }
+}
+
+/// Start, control 1, control 2, end
+impl From<(Vec2, Vec2, Vec2, Vec2)> for CubicBezier {
+    fn from(points: (Vec2, Vec2, Vec2, Vec2)) -> Self {
+        Self::new(points.0, points.1, points.2, points.3)
+    }
+}
+
+impl From<QuadBezier> for CubicBezier {
+    fn from(quad: QuadBezier) -> Self {
+        quad.to_cubic()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::core::*;
+
+    fn arch() -> CubicBezier {
+        CubicBezier::new(
+            Vec2::xy(0., 0.),
+            Vec2::xy(0., 100.),
+            Vec2::xy(100., 100.),
+            Vec2::xy(100., 0.),
+        )
+    }
+
+    fn assert_close(a: Vec2, b: Vec2) {
+        assert!((a - b).norm() < 1e-9, "{a} != {b}");
+    }
+
+    #[test]
+    fn end_points() {
+        let curve = arch();
+        assert_eq!(curve.at(0.), curve.start);
+        assert_eq!(curve.at(1.), curve.end);
+        assert_close(curve.at(0.5), Vec2::xy(50., 75.));
+    }
+
+    #[test]
+    fn split_matches_original() {
+        let curve = arch();
+        let (left, right) = curve.split(0.3);
+        assert_close(left.at(0.5), curve.at(0.15));
+        assert_close(right.at(0.5), curve.at(0.65));
+        assert_eq!(left.end, right.start);
+    }
+
+    #[test]
+    fn derivative_matches_finite_difference() {
+        let curve = arch();
+        let h = 1e-6;
+        let numeric = (curve.at(0.4 + h) - curve.at(0.4 - h)) / (2. * h);
+        assert!((curve.derivative(0.4) - numeric).norm() < 1e-4);
+    }
+
+    #[test]
+    fn quad_to_cubic() {
+        let quad = QuadBezier::new(Vec2::xy(0., 0.), Vec2::xy(50., 100.), Vec2::xy(100., 0.));
+        let cubic = quad.to_cubic();
+        for t in [0., 0.2, 0.5, 0.9] {
+            assert_close(quad.at(t), cubic.at(t));
+        }
+    }
+
+    #[test]
+    fn length_of_straight_curve() {
+        let curve = CubicBezier::new(
+            Vec2::xy(0., 0.),
+            Vec2::xy(10., 0.),
+            Vec2::xy(20., 0.),
+            Vec2::xy(100., 0.),
+        );
+        assert!((curve.length() - 100.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn length_of_quarter_circle() {
+        // Standard cubic approximation of a unit quarter circle.
+        let k = 0.552_284_749_8;
+        let curve = CubicBezier::new(
+            Vec2::xy(1., 0.),
+            Vec2::xy(1., k),
+            Vec2::xy(k, 1.),
+            Vec2::xy(0., 1.),
+        );
+        assert!((curve.length() - std::f64::consts::FRAC_PI_2).abs() < 1e-3);
+    }
+
+    #[test]
+    fn tight_bb() {
+        let bb = arch().bb();
+        assert_close(bb.min(), Vec2::xy(0., 0.));
+        assert_close(bb.max(), Vec2::xy(100., 75.));
+    }
+
+    #[test]
+    fn flatten_within_tolerance() {
+        let curve = arch();
+        let tolerance = 0.1;
+        let points = curve.flatten(tolerance);
+        assert_eq!(points.first(), Some(&curve.start));
+        assert_eq!(points.last(), Some(&curve.end));
+        for segment in points.windows(2) {
+            let chord = Line::from_to(segment[0], segment[1]);
+            // Find the curve parameter of the chord’s midpoint by brute force.
+            let deviation = (0 ..= 20_000)
+                .map(|i| curve.at(i as f64 / 20_000.))
+                .map(|p| (p - chord.at(0.5)).norm())
+                .fold(f64::INFINITY, f64::min);
+            assert!(deviation <= tolerance);
+        }
+        assert!(points.len() < 100);
+    }
+
+    #[test]
+    fn flatten_collinear_overshoot() {
+        // All controls are on the x axis, but beyond the end points.
+        let curve = CubicBezier::new(
+            Vec2::xy(0., 0.),
+            Vec2::xy(100., 0.),
+            Vec2::xy(-100., 0.),
+            Vec2::xy(10., 0.),
+        );
+        let points = curve.flatten(0.1);
+        let min_x = points.iter().map(|p| p.x).fold(f64::INFINITY, f64::min);
+        let max_x = points.iter().map(|p| p.x).fold(-f64::INFINITY, f64::max);
+        assert!((min_x - -24.1).abs() < 0.2, "{min_x}");
+        assert!((max_x - 29.).abs() < 0.2, "{max_x}");
+
+        let quad = QuadBezier::new(Vec2::xy(0., 0.), Vec2::xy(20., 0.), Vec2::xy(5., 0.));
+        let max_x = quad.flatten(0.1).iter().map(|p| p.x).fold(0., f64::max);
+        // The quadratic turns around at x = 400/35.
+        assert!((max_x - 400. / 35.).abs() < 0.2, "{max_x}");
+    }
+}
@@ -1,3 +1,4 @@
+use super::bb::*;
 use super::vec2::*;
 
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -18,3 +19,10 @@ impl From<(Vec2, f64)> for Circle {
         Circle::new(value.0, value.1)
     }
 }
+
+impl HasBB for Circle {
+    fn bb(&self) -> BB {
+        let r = Vec2::xy(self.radius, self.radius);
+        (self.center - r, self.center + r).bb()
+    }
+}
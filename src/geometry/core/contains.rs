@@ -0,0 +1,141 @@
+use super::bb::*;
+use super::circle::*;
+use super::line::*;
+use super::multi_polygon::*;
+use super::polygon::*;
+use super::triangle::*;
+use super::vec2::*;
+use impl_trait_for_tuples::impl_for_tuples;
+
+/// Point-in-region test.
+pub trait Contains {
+    /// Check whether a point lies inside. Points exactly on the boundary may be
+    /// reported as either inside or outside.
+    fn contains(&self, point: Vec2) -> bool;
+}
+
+impl Contains for BB {
+    fn contains(&self, point: Vec2) -> bool {
+        let (min, max) = (self.min(), self.max());
+        min.x <= point.x && point.x <= max.x && min.y <= point.y && point.y <= max.y
+    }
+}
+
+impl Contains for Circle {
+    fn contains(&self, point: Vec2) -> bool {
+        (point - self.center).norm_square() <= self.radius * self.radius
+    }
+}
+
+/// Self-intersecting polygons are handled with the even-odd rule.
+impl Contains for Polygon {
+    fn contains(&self, point: Vec2) -> bool {
+        let mut inside = false;
+        for Line { start: a, end: b } in self.edges() {
+            if (a.y > point.y) != (b.y > point.y) {
+                let x = a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x);
+                if point.x < x {
+                    inside = !inside;
+                }
+            }
+        }
+        inside
+    }
+}
+
+impl Contains for Triangle {
+    fn contains(&self, point: Vec2) -> bool {
+        let [u, v, w] = self.barycentric(point);
+        u >= 0. && v >= 0. && w >= 0.
+    }
+}
+
+impl Contains for PolygonWithHoles {
+    fn contains(&self, point: Vec2) -> bool {
+        self.outer.contains(point) && !self.holes.iter().any(|hole| hole.contains(point))
+    }
+}
+
+impl Contains for MultiPolygon {
+    fn contains(&self, point: Vec2) -> bool {
+        self.parts().any(|part| part.contains(point))
+    }
+}
+
+impl<T: Contains + ?Sized> Contains for &T {
+    fn contains(&self, point: Vec2) -> bool {
+        (**self).contains(point)
+    }
+}
+
+/// Union of all elements.
+impl<T: Contains> Contains for [T] {
+    fn contains(&self, point: Vec2) -> bool {
+        self.iter().any(|region| region.contains(point))
+    }
+}
+
+/// Union of all elements.
+impl<T: Contains> Contains for Vec<T> {
+    fn contains(&self, point: Vec2) -> bool {
+        self.iter().any(|region| region.contains(point))
+    }
+}
+
+#[impl_for_tuples(1, 3)]
+/// A tuple contains the union of its elements’ regions.
+impl Contains for Tuple {
+    fn contains(&self, point: Vec2) -> bool {
+        for_tuples!( #( if Tuple.contains(point) { return true; } )* );
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::core::*;
+
+    #[test]
+    fn primitives() {
+        let bb = (Vec2::xy(0., 0.), Vec2::xy(10., 5.)).bb();
+        assert!(bb.contains(Vec2::xy(10., 5.)));
+        assert!(!bb.contains(Vec2::xy(10.1, 5.)));
+
+        let circle = Circle::new(Vec2::xy(1., 1.), 2.);
+        assert!(circle.contains(Vec2::xy(2., 2.)));
+        assert!(!circle.contains(Vec2::xy(3., 3.)));
+    }
+
+    #[test]
+    fn polygon_with_holes() {
+        let square = |min: f64, max: f64| {
+            Polygon::from_points(vec![
+                Vec2::xy(min, min),
+                Vec2::xy(max, min),
+                Vec2::xy(max, max),
+                Vec2::xy(min, max),
+            ])
+        };
+        let frame = PolygonWithHoles::new(square(0., 10.), vec![square(3., 7.)]);
+        assert!(frame.contains(Vec2::xy(1., 1.)));
+        assert!(!frame.contains(Vec2::xy(5., 5.)));
+        assert!(!frame.contains(Vec2::xy(11., 5.)));
+
+        let islands = MultiPolygon::from_parts(vec![frame, square(4., 6.).into()]);
+        assert!(islands.contains(Vec2::xy(5., 5.)));
+        assert!(!islands.contains(Vec2::xy(3.5, 5.)));
+    }
+
+    #[test]
+    fn compositions_are_unions() {
+        let a = Circle::new(Vec2::xy(0., 0.), 1.);
+        let b = Circle::new(Vec2::xy(10., 0.), 1.);
+        let c = (Vec2::xy(4., 4.), Vec2::xy(6., 6.)).bb();
+        for p in [Vec2::xy(0.5, 0.), Vec2::xy(10., 0.5), Vec2::xy(5., 5.)] {
+            assert!((a, b, c).contains(p));
+        }
+        assert!(!(a, b, c).contains(Vec2::xy(5., 0.)));
+        assert!(vec![a, b].contains(Vec2::xy(10., 0.5)));
+        assert!(!vec![a, b].contains(Vec2::xy(5., 5.)));
+    }
+}
@@ -35,6 +35,60 @@ impl Line {
         self.vec2().angle()
     }
 
+    pub fn at(self, t: f64) -> Vec2 {
+        self.start + self.vec2() * t
+    }
+
+    /// Distance from a point to the closest point on the segment.
+    pub fn distance_to(self, point: Vec2) -> f64 {
+        let v = self.vec2();
+        let t = if v == Vec2::xy(0., 0.) {
+            0.
+        } else {
+            ((point - self.start).dot(v) / v.norm_square()).clamp(0., 1.)
+        };
+        (self.at(t) - point).norm()
+    }
+
+    /// Intersect two segments.
+    ///
+    /// Parameters refer to [`Line::at`] of `self` (t) and `other` (u).
+    pub fn intersect(self, other: Line) -> Intersection {
+        self.intersect_with_tolerance(
+            other,
+            default_tolerance(&[self.start, self.end, other.start, other.end]),
+        )
+    }
+
+    /// Like [`Line::intersect`], but points closer than `tolerance` count as
+    /// touching. This is useful to get consistent results when intersecting
+    /// many segments of a common scene.
+    pub fn intersect_with_tolerance(self, other: Line, tolerance: f64) -> Intersection {
+        intersection(
+            self.start,
+            self.vec2(),
+            Extent::Segment,
+            other,
+            Extent::Segment,
+            tolerance,
+        )
+    }
+
+    /// Intersect the segment with the infinite line through `line`.
+    ///
+    /// The parameter u along the infinite line is unbounded.
+    pub fn intersect_line(self, line: Line) -> Intersection {
+        let tolerance = default_tolerance(&[self.start, self.end, line.start, line.end]);
+        intersection(
+            self.start,
+            self.vec2(),
+            Extent::Segment,
+            line,
+            Extent::Infinite,
+            tolerance,
+        )
+    }
+
     pub fn subdivide_n(self, num_segments: usize) -> Vec<Vec2> {
         let mut result = Vec::with_capacity(num_segments + 1);
         result.push(self.start);
@@ -55,6 +109,198 @@ impl Line {
     }
 }
 
+/// Half-infinite line.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Ray {
+    pub origin: Vec2,
+    pub direction: Vec2,
+}
+
+impl Ray {
+    pub fn new(origin: Vec2, direction: Vec2) -> Self {
+        Self { origin, direction }
+    }
+
+    pub fn angled(origin: Vec2, angle: Angle) -> Self {
+        Self::new(origin, Vec2::polar(1., angle))
+    }
+
+    /// Point at `origin + t * direction`.
+    pub fn at(self, t: f64) -> Vec2 {
+        self.origin + self.direction * t
+    }
+
+    /// Intersect the ray with a segment.
+    ///
+    /// The parameter t along the ray is in multiples of `direction`, u is
+    /// along the segment as in [`Line::at`].
+    pub fn intersect(self, segment: Line) -> Intersection {
+        let tolerance = default_tolerance(&[self.origin, segment.start, segment.end]);
+        intersection(
+            self.origin,
+            self.direction,
+            Extent::Ray,
+            segment,
+            Extent::Segment,
+            tolerance,
+        )
+    }
+}
+
+/// Common points of two linear objects.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Intersection {
+    /// No common points. This includes parallel objects that are not
+    /// collinear.
+    None,
+    /// A single common point, at parameter t of the first and u of the second
+    /// object.
+    Point { point: Vec2, t: f64, u: f64 },
+    /// Collinear objects sharing a segment, oriented like the first object.
+    /// `t` and `u` are the parameters of the overlap’s start and end along
+    /// both objects.
+    Overlap {
+        segment: Line,
+        t: (f64, f64),
+        u: (f64, f64),
+    },
+}
+
+impl Intersection {
+    /// All distinct common points: none, the intersection point, or the two
+    /// end points of an overlap.
+    pub fn points(self) -> Vec<Vec2> {
+        match self {
+            Intersection::None => Vec::new(),
+            Intersection::Point { point, .. } => vec![point],
+            Intersection::Overlap { segment, .. } => vec![segment.start, segment.end],
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Extent {
+    Segment,
+    Ray,
+    Infinite,
+}
+
+impl Extent {
+    fn range(self) -> (f64, f64) {
+        match self {
+            Extent::Segment => (0., 1.),
+            Extent::Ray => (0., f64::INFINITY),
+            Extent::Infinite => (f64::NEG_INFINITY, f64::INFINITY),
+        }
+    }
+}
+
+fn default_tolerance(points: &[Vec2]) -> f64 {
+    let magnitude = points
+        .iter()
+        .map(|p| p.x.abs().max(p.y.abs()))
+        .fold(1., f64::max);
+    magnitude * 1e-10
+}
+
+/// Intersect `origin + t * r` (t in `extent_r`) with `other` (u in `extent_s`).
+fn intersection(
+    origin: Vec2,
+    r: Vec2,
+    extent_r: Extent,
+    other: Line,
+    extent_s: Extent,
+    tolerance: f64,
+) -> Intersection {
+    let s = other.vec2();
+    let (r_len, s_len) = (r.norm(), s.norm());
+    if r_len <= tolerance || s_len <= tolerance {
+        return Intersection::None;
+    }
+    let (t_lo, t_hi) = extent_r.range();
+    let (u_lo, u_hi) = extent_s.range();
+    let (t_eps, u_eps) = (tolerance / r_len, tolerance / s_len);
+    let at_r = |t: f64| origin + r * t;
+    let u_of = |p: Vec2| (p - other.start).dot(s) / (s_len * s_len);
+    let t_of = |p: Vec2| (p - origin).dot(r) / (r_len * r_len);
+    let point = |t: f64, u: f64| Intersection::Point {
+        point: at_r(t),
+        t,
+        u,
+    };
+
+    let denominator = r.cross(s);
+    let qp = other.start - origin;
+    let parallel = denominator.abs() <= 1e-12 * r_len * s_len;
+    if !parallel {
+        let t = qp.cross(s) / denominator;
+        let u = qp.cross(r) / denominator;
+        if t >= t_lo - t_eps && t <= t_hi + t_eps && u >= u_lo - u_eps && u <= u_hi + u_eps {
+            return point(t.clamp(t_lo, t_hi), u.clamp(u_lo, u_hi));
+        }
+
+        // Nearly parallel objects touching at an end point can miss the above
+        // test due to rounding, so check the end points explicitly.
+        let ends_r = [t_lo, t_hi]
+            .into_iter()
+            .filter(|t| t.is_finite())
+            .map(|t| (t, at_r(t)));
+        for (t, p) in ends_r {
+            let u = u_of(p).clamp(u_lo, u_hi);
+            if (other.at(u) - p).norm() <= tolerance {
+                return point(t, u);
+            }
+        }
+        let ends_s = [u_lo, u_hi]
+            .into_iter()
+            .filter(|u| u.is_finite())
+            .map(|u| (u, other.at(u)));
+        for (u, p) in ends_s {
+            let t = t_of(p).clamp(t_lo, t_hi);
+            if (at_r(t) - p).norm() <= tolerance {
+                return point(t, u);
+            }
+        }
+        return Intersection::None;
+    }
+
+    let distance = qp.cross(r).abs() / r_len;
+    if distance > tolerance {
+        return Intersection::None;
+    }
+
+    // Collinear: intersect the parameter ranges, measured along r.
+    let (mut lo, mut hi) = match extent_s {
+        Extent::Segment => {
+            let (a, b) = (t_of(other.start), t_of(other.end));
+            (a.min(b), a.max(b))
+        }
+        Extent::Ray => {
+            let a = t_of(other.start);
+            if s.dot(r) > 0. {
+                (a, f64::INFINITY)
+            } else {
+                (f64::NEG_INFINITY, a)
+            }
+        }
+        Extent::Infinite => (f64::NEG_INFINITY, f64::INFINITY),
+    };
+    lo = lo.max(t_lo);
+    hi = hi.min(t_hi);
+    if lo > hi + t_eps {
+        Intersection::None
+    } else if hi - lo <= t_eps {
+        let t = (lo + hi) / 2.;
+        point(t, u_of(at_r(t)))
+    } else {
+        Intersection::Overlap {
+            segment: Line::from_to(at_r(lo), at_r(hi)),
+            t: (lo, hi),
+            u: (u_of(at_r(lo)), u_of(at_r(hi))),
+        }
+    }
+}
+
 /// Start, end
 impl From<(Vec2, Vec2)> for Line {
     fn from(ab: (Vec2, Vec2)) -> Self {
@@ -93,4 +339,120 @@ mod tests {
         let result = line.subdivide_by_length(segment_length);
         assert_eq!(result.len(), 10);
     }
+
+    #[test]
+    fn intersect_crossing() {
+        let a = Line::from_to(Vec2::xy(0., 0.), Vec2::xy(10., 10.));
+        let b = Line::from_to(Vec2::xy(0., 10.), Vec2::xy(10., 0.));
+        let expected = Intersection::Point {
+            point: Vec2::xy(5., 5.),
+            t: 0.5,
+            u: 0.5,
+        };
+        assert_eq!(a.intersect(b), expected);
+    }
+
+    #[test]
+    fn intersect_touching_end_point() {
+        let a = Line::from_to(Vec2::xy(0., 0.), Vec2::xy(10., 0.));
+        let b = Line::from_to(Vec2::xy(5., 0.), Vec2::xy(5., 10.));
+        match a.intersect(b) {
+            Intersection::Point { point, t, u } => {
+                assert_eq!(point, Vec2::xy(5., 0.));
+                assert_eq!((t, u), (0.5, 0.));
+            }
+            other => panic!("Expected point, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn intersect_missing() {
+        let a = Line::from_to(Vec2::xy(0., 0.), Vec2::xy(10., 0.));
+        let b = Line::from_to(Vec2::xy(5., 1.), Vec2::xy(5., 10.));
+        assert_eq!(a.intersect(b), Intersection::None);
+    }
+
+    #[test]
+    fn intersect_parallel() {
+        let a = Line::from_to(Vec2::xy(0., 0.), Vec2::xy(10., 0.));
+        let b = Line::from_to(Vec2::xy(0., 1.), Vec2::xy(10., 1.));
+        assert_eq!(a.intersect(b), Intersection::None);
+    }
+
+    #[test]
+    fn intersect_collinear_overlap() {
+        let a = Line::from_to(Vec2::xy(0., 0.), Vec2::xy(10., 0.));
+        let b = Line::from_to(Vec2::xy(15., 0.), Vec2::xy(5., 0.));
+        let expected = Intersection::Overlap {
+            segment: Line::from_to(Vec2::xy(5., 0.), Vec2::xy(10., 0.)),
+            t: (0.5, 1.),
+            u: (1., 0.5),
+        };
+        assert_eq!(a.intersect(b), expected);
+    }
+
+    #[test]
+    fn intersect_collinear_disjoint() {
+        let a = Line::from_to(Vec2::xy(0., 0.), Vec2::xy(10., 0.));
+        let b = Line::from_to(Vec2::xy(11., 0.), Vec2::xy(20., 0.));
+        assert_eq!(a.intersect(b), Intersection::None);
+    }
+
+    #[test]
+    fn intersect_collinear_touching() {
+        let a = Line::from_to(Vec2::xy(0., 0.), Vec2::xy(10., 0.));
+        let b = Line::from_to(Vec2::xy(10., 0.), Vec2::xy(20., 0.));
+        let expected = Intersection::Point {
+            point: Vec2::xy(10., 0.),
+            t: 1.,
+            u: 0.,
+        };
+        assert_eq!(a.intersect(b), expected);
+    }
+
+    #[test]
+    fn intersect_infinite_line() {
+        let segment = Line::from_to(Vec2::xy(0., 0.), Vec2::xy(10., 0.));
+        let line = Line::from_to(Vec2::xy(5., 1.), Vec2::xy(5., 2.));
+        let expected = Intersection::Point {
+            point: Vec2::xy(5., 0.),
+            t: 0.5,
+            u: -1.,
+        };
+        assert_eq!(segment.intersect_line(line), expected);
+    }
+
+    #[test]
+    fn intersect_ray() {
+        let segment = Line::from_to(Vec2::xy(10., -5.), Vec2::xy(10., 5.));
+        let ray = Ray::new(Vec2::xy(0., 0.), Vec2::xy(2., 0.));
+        let expected = Intersection::Point {
+            point: Vec2::xy(10., 0.),
+            t: 5.,
+            u: 0.5,
+        };
+        assert_eq!(ray.intersect(segment), expected);
+
+        let backwards = Ray::new(Vec2::xy(0., 0.), Vec2::xy(-1., 0.));
+        assert_eq!(backwards.intersect(segment), Intersection::None);
+    }
+
+    #[test]
+    fn intersect_ray_collinear() {
+        let segment = Line::from_to(Vec2::xy(-5., 0.), Vec2::xy(5., 0.));
+        let ray = Ray::new(Vec2::xy(0., 0.), Vec2::xy(1., 0.));
+        let expected = Intersection::Overlap {
+            segment: Line::from_to(Vec2::xy(0., 0.), Vec2::xy(5., 0.)),
+            t: (0., 5.),
+            u: (0.5, 1.),
+        };
+        assert_eq!(ray.intersect(segment), expected);
+    }
+
+    #[test]
+    fn distance_to() {
+        let line = Line::from_to(Vec2::xy(0., 0.), Vec2::xy(10., 0.));
+        assert_eq!(line.distance_to(Vec2::xy(5., 3.)), 3.);
+        assert_eq!(line.distance_to(Vec2::xy(13., 4.)), 5.);
+    }
 }
@@ -1,15 +1,23 @@
 mod angle;
 mod bb;
+mod bezier;
 mod circle;
+mod contains;
 mod line;
+mod multi_polygon;
 mod polygon;
 mod transformation;
+mod triangle;
 mod vec2;
 
 pub use angle::*;
 pub use bb::*;
+pub use bezier::*;
 pub use circle::*;
+pub use contains::*;
 pub use line::*;
+pub use multi_polygon::*;
 pub use polygon::*;
 pub use transformation::*;
+pub use triangle::*;
 pub use vec2::*;
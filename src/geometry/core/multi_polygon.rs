@@ -0,0 +1,101 @@
+use super::bb::*;
+use super::line::*;
+use super::polygon::*;
+
+/// A polygon with holes.
+///
+/// The outer boundary has [`Orientation::Positive`], the holes have
+/// [`Orientation::Negative`]. This is the form produced by the boolean
+/// operations.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PolygonWithHoles {
+    pub outer: Polygon,
+    pub holes: Vec<Polygon>,
+}
+
+impl PolygonWithHoles {
+    pub fn new(outer: Polygon, holes: Vec<Polygon>) -> Self {
+        Self { outer, holes }
+    }
+
+    /// All rings, outer boundary first.
+    pub fn rings(&self) -> impl Iterator<Item = &Polygon> {
+        std::iter::once(&self.outer).chain(self.holes.iter())
+    }
+
+    pub fn edges(&self) -> impl Iterator<Item = Line> + '_ {
+        self.rings().flat_map(|ring| ring.edges())
+    }
+
+    pub fn area(&self) -> f64 {
+        self.outer.area() - self.holes.iter().map(|hole| hole.area()).sum::<f64>()
+    }
+}
+
+impl HasBB for PolygonWithHoles {
+    fn bb(&self) -> BB {
+        self.outer.bb()
+    }
+}
+
+impl From<Polygon> for PolygonWithHoles {
+    fn from(outer: Polygon) -> Self {
+        Self::new(outer, Vec::new())
+    }
+}
+
+/// A collection of non-overlapping polygons with holes.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct MultiPolygon(Vec<PolygonWithHoles>);
+
+impl MultiPolygon {
+    pub fn from_parts(parts: Vec<PolygonWithHoles>) -> Self {
+        Self(parts)
+    }
+
+    pub fn parts(&self) -> impl Iterator<Item = &PolygonWithHoles> + Clone {
+        self.0.iter()
+    }
+
+    pub fn into_parts(self) -> Vec<PolygonWithHoles> {
+        self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn rings(&self) -> impl Iterator<Item = &Polygon> {
+        self.parts().flat_map(|part| part.rings())
+    }
+
+    pub fn edges(&self) -> impl Iterator<Item = Line> + '_ {
+        self.parts().flat_map(|part| part.edges())
+    }
+
+    pub fn area(&self) -> f64 {
+        self.parts().map(|part| part.area()).sum()
+    }
+}
+
+impl HasBB for MultiPolygon {
+    fn bb(&self) -> BB {
+        self.parts().map(|part| part.bb()).sum()
+    }
+}
+
+impl From<Polygon> for MultiPolygon {
+    fn from(polygon: Polygon) -> Self {
+        Self(vec![polygon.into()])
+    }
+}
+
+impl From<PolygonWithHoles> for MultiPolygon {
+    fn from(polygon: PolygonWithHoles) -> Self {
+        Self(vec![polygon])
+    }
+}
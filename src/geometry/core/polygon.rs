@@ -25,7 +25,6 @@ impl Polygon {
     }
 
     pub fn edges(&self) -> impl Iterator<Item = Line> + '_ {
-        let points = self.points();
         self.points()
             .zip(self.points().cycle().skip(1))
             .map(|(p1, p2)| Line::from_to(*p1, *p2))
@@ -54,6 +53,24 @@ impl Polygon {
         self.area_signed().abs()
     }
 
+    /// Center of mass of the enclosed area.
+    ///
+    /// Degenerate polygons without area fall back to the average of the
+    /// corners.
+    pub fn centroid(&self) -> Vec2 {
+        let area = self.area_signed();
+        if area == 0. {
+            let n = self.0.len().max(1) as f64;
+            return self.points().fold(Vec2::xy(0., 0.), |acc, p| acc + *p) / n;
+        }
+        let moment = self
+            .edges()
+            .fold(Vec2::xy(0., 0.), |acc, Line { start, end }| {
+                acc + (start + end) * start.cross(end)
+            });
+        moment / (6. * area)
+    }
+
     /// Check whether the polygon is convex.
     ///
     /// To avoid numerically unstable near-straight edges, we consider
@@ -99,6 +116,19 @@ impl From<Vec<Vec2>> for Polygon {
     }
 }
 
+/// Rectangle with the corners of the bounding box, in positive orientation.
+impl From<BB> for Polygon {
+    fn from(bb: BB) -> Self {
+        let (min, max) = (bb.min(), bb.max());
+        Self::from_points(vec![
+            min,
+            Vec2::xy(max.x, min.y),
+            max,
+            Vec2::xy(min.x, max.y),
+        ])
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::geometry::core::*;
@@ -151,4 +181,35 @@ mod tests {
         ]);
         assert_eq!(standard_square.orientation(), Orientation::Positive);
     }
+
+    #[test]
+    fn contains_for_concave() {
+        let polygon = Polygon::from(vec![
+            Vec2::xy(110., 10.),
+            Vec2::xy(110., 90.),
+            Vec2::xy(150., 50.),
+            Vec2::xy(190., 90.),
+            Vec2::xy(190., 10.),
+        ]);
+        assert!(polygon.contains(Vec2::xy(150., 30.)));
+        assert!(polygon.contains(Vec2::xy(115., 80.)));
+        assert!(!polygon.contains(Vec2::xy(150., 80.)));
+        assert!(!polygon.contains(Vec2::xy(200., 50.)));
+    }
+
+    #[test]
+    fn centroid_of_l_shape() {
+        let mut points = vec![
+            Vec2::xy(0., 0.),
+            Vec2::xy(2., 0.),
+            Vec2::xy(2., 1.),
+            Vec2::xy(1., 1.),
+            Vec2::xy(1., 2.),
+            Vec2::xy(0., 2.),
+        ];
+        let expected = Vec2::xy(5. / 6., 5. / 6.);
+        assert!((Polygon::from(points.clone()).centroid() - expected).norm() < 1e-12);
+        points.reverse();
+        assert!((Polygon::from(points).centroid() - expected).norm() < 1e-12);
+    }
 }
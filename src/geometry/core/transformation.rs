@@ -133,7 +133,7 @@ impl MulAssign for Transformation {
     }
 }
 
-trait Transform {
+pub trait Transform {
     fn transform(&self, t: Transformation) -> Self;
     fn transform_mut(&mut self, t: Transformation);
 }
@@ -147,12 +147,11 @@ impl Transform for Vec2 {
     }
 
     fn transform_mut(&mut self, t: Transformation) {
-        self.x = t.m11 * self.x + t.m12 * self.y + t.b1;
-        self.y = t.m21 * self.x + t.m22 * self.y + t.b2;
+        *self = self.transform(t);
     }
 }
 
-impl Transform for Vec<Vec2> {
+impl<T: Transform> Transform for Vec<T> {
     fn transform(&self, t: Transformation) -> Self {
         self.iter().map(|p| p.transform(t)).collect()
     }
@@ -200,11 +199,11 @@ mod transform_line_test {
 
 impl Transform for Polygon {
     fn transform(&self, t: Transformation) -> Self {
-        Polygon::from_points(self.points().transform(t))
+        Polygon::from_points(self.points().map(|p| p.transform(t)).collect())
     }
 
     fn transform_mut(&mut self, t: Transformation) {
-        self.points_mut().transform_mut(t);
+        self.points_mut().for_each(|p| p.transform_mut(t));
     }
 }
 
@@ -235,3 +234,53 @@ mod transform_polygon_test {
         assert_eq!(polygon, expected);
     }
 }
+
+impl Transform for QuadBezier {
+    fn transform(&self, t: Transformation) -> Self {
+        QuadBezier::new(
+            self.start.transform(t),
+            self.control.transform(t),
+            self.end.transform(t),
+        )
+    }
+
+    fn transform_mut(&mut self, t: Transformation) {
+        self.start.transform_mut(t);
+        self.control.transform_mut(t);
+        self.end.transform_mut(t);
+    }
+}
+
+impl Transform for CubicBezier {
+    fn transform(&self, t: Transformation) -> Self {
+        CubicBezier::new(
+            self.start.transform(t),
+            self.control1.transform(t),
+            self.control2.transform(t),
+            self.end.transform(t),
+        )
+    }
+
+    fn transform_mut(&mut self, t: Transformation) {
+        self.start.transform_mut(t);
+        self.control1.transform_mut(t);
+        self.control2.transform_mut(t);
+        self.end.transform_mut(t);
+    }
+}
+
+impl Transform for Triangle {
+    fn transform(&self, t: Transformation) -> Self {
+        Triangle::new(
+            self.a.transform(t),
+            self.b.transform(t),
+            self.c.transform(t),
+        )
+    }
+
+    fn transform_mut(&mut self, t: Transformation) {
+        self.a.transform_mut(t);
+        self.b.transform_mut(t);
+        self.c.transform_mut(t);
+    }
+}
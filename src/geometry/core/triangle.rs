@@ -0,0 +1,187 @@
+use super::bb::*;
+use super::circle::*;
+use super::line::*;
+use super::polygon::*;
+use super::vec2::*;
+use rand::Rng;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Triangle {
+    pub a: Vec2,
+    pub b: Vec2,
+    pub c: Vec2,
+}
+
+impl Triangle {
+    pub fn new(a: Vec2, b: Vec2, c: Vec2) -> Self {
+        Self { a, b, c }
+    }
+
+    pub fn points(self) -> [Vec2; 3] {
+        [self.a, self.b, self.c]
+    }
+
+    pub fn edges(self) -> [Line; 3] {
+        [
+            Line::from_to(self.a, self.b),
+            Line::from_to(self.b, self.c),
+            Line::from_to(self.c, self.a),
+        ]
+    }
+
+    pub fn area_signed(self) -> f64 {
+        (self.b - self.a).cross(self.c - self.a) / 2.
+    }
+
+    pub fn area(self) -> f64 {
+        self.area_signed().abs()
+    }
+
+    pub fn orientation(self) -> Orientation {
+        if self.area_signed() > 0. {
+            Orientation::Positive
+        } else {
+            Orientation::Negative
+        }
+    }
+
+    /// Same triangle with the corners in opposite order.
+    pub fn reverse(self) -> Self {
+        Self::new(self.a, self.c, self.b)
+    }
+
+    pub fn centroid(self) -> Vec2 {
+        (self.a + self.b + self.c) / 3.
+    }
+
+    /// Circle through all three corners. Degenerate (collinear) triangles have
+    /// no finite circumcircle.
+    pub fn circumcircle(self) -> Circle {
+        let (ab, ac) = (self.b - self.a, self.c - self.a);
+        let d = 2. * ab.cross(ac);
+        let offset = Vec2::xy(
+            ac.y * ab.norm_square() - ab.y * ac.norm_square(),
+            ab.x * ac.norm_square() - ac.x * ab.norm_square(),
+        ) / d;
+        Circle::new(self.a + offset, offset.norm())
+    }
+
+    /// Largest circle inside the triangle, touching all edges.
+    pub fn incircle(self) -> Circle {
+        // Corners weighted by the length of the opposite edge.
+        let la = (self.c - self.b).norm();
+        let lb = (self.a - self.c).norm();
+        let lc = (self.b - self.a).norm();
+        let perimeter = la + lb + lc;
+        let center = (self.a * la + self.b * lb + self.c * lc) / perimeter;
+        Circle::new(center, 2. * self.area() / perimeter)
+    }
+
+    /// Barycentric coordinates `[u, v, w]` of a point, so that
+    /// `point = u*a + v*b + w*c` and `u + v + w = 1`.
+    pub fn barycentric(self, point: Vec2) -> [f64; 3] {
+        let area = self.area_signed();
+        let u = Triangle::new(point, self.b, self.c).area_signed() / area;
+        let v = Triangle::new(self.a, point, self.c).area_signed() / area;
+        [u, v, 1. - u - v]
+    }
+
+    /// Inverse of [`Triangle::barycentric`].
+    pub fn from_barycentric(self, [u, v, w]: [f64; 3]) -> Vec2 {
+        self.a * u + self.b * v + self.c * w
+    }
+
+    /// Uniformly distributed random point inside the triangle.
+    pub fn sample<R: Rng + ?Sized>(self, rng: &mut R) -> Vec2 {
+        let (mut s, mut t): (f64, f64) = (rng.gen(), rng.gen());
+        // Reflect the upper half of the unit square onto the lower one.
+        if s + t > 1. {
+            (s, t) = (1. - s, 1. - t);
+        }
+        self.a + (self.b - self.a) * s + (self.c - self.a) * t
+    }
+}
+
+impl HasBB for Triangle {
+    fn bb(&self) -> BB {
+        (self.a, self.b, self.c).bb()
+    }
+}
+
+impl From<(Vec2, Vec2, Vec2)> for Triangle {
+    fn from((a, b, c): (Vec2, Vec2, Vec2)) -> Self {
+        Triangle::new(a, b, c)
+    }
+}
+
+impl From<Triangle> for Polygon {
+    fn from(triangle: Triangle) -> Self {
+        Polygon::from_points(triangle.points().to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::core::*;
+    use rand::{rngs::SmallRng, SeedableRng};
+
+    fn right_triangle() -> Triangle {
+        Triangle::new(Vec2::xy(0., 0.), Vec2::xy(4., 0.), Vec2::xy(0., 3.))
+    }
+
+    #[test]
+    fn area_and_orientation() {
+        let triangle = right_triangle();
+        assert_eq!(triangle.area(), 6.);
+        assert_eq!(triangle.orientation(), Orientation::Positive);
+        assert_eq!(triangle.reverse().orientation(), Orientation::Negative);
+        assert_eq!(
+            triangle.area_signed(),
+            Polygon::from(triangle).area_signed()
+        );
+    }
+
+    #[test]
+    fn circles() {
+        let circumcircle = right_triangle().circumcircle();
+        // Thales: the hypotenuse is a diameter.
+        assert!((circumcircle.center - Vec2::xy(2., 1.5)).norm() < 1e-12);
+        assert!((circumcircle.radius - 2.5).abs() < 1e-12);
+
+        // Inradius of a right triangle is (a + b - c) / 2.
+        let incircle = right_triangle().incircle();
+        assert!((incircle.center - Vec2::xy(1., 1.)).norm() < 1e-12);
+        assert!((incircle.radius - 1.).abs() < 1e-12);
+    }
+
+    #[test]
+    fn barycentric_round_trip() {
+        let triangle = Triangle::new(Vec2::xy(1., 2.), Vec2::xy(7., -1.), Vec2::xy(3., 5.));
+        assert_eq!(triangle.barycentric(triangle.b), [0., 1., 0.]);
+        let point = Vec2::xy(3.5, 1.25);
+        let coordinates = triangle.barycentric(point);
+        assert!((coordinates.iter().sum::<f64>() - 1.).abs() < 1e-12);
+        assert!((triangle.from_barycentric(coordinates) - point).norm() < 1e-12);
+    }
+
+    #[test]
+    fn containment_in_both_orientations() {
+        for triangle in [right_triangle(), right_triangle().reverse()] {
+            assert!(triangle.contains(Vec2::xy(1., 1.)));
+            assert!(triangle.contains(Vec2::xy(2., 0.)));
+            assert!(!triangle.contains(Vec2::xy(3., 3.)));
+            assert!(!triangle.contains(Vec2::xy(-0.1, 1.)));
+        }
+    }
+
+    #[test]
+    fn samples_are_inside_and_uniform() {
+        let triangle = right_triangle();
+        let mut rng = SmallRng::seed_from_u64(0);
+        let samples: Vec<Vec2> = (0 .. 10_000).map(|_| triangle.sample(&mut rng)).collect();
+        assert!(samples.iter().all(|p| triangle.contains(*p)));
+        // The mean of uniform samples is the centroid.
+        let mean = samples.iter().fold(Vec2::xy(0., 0.), |acc, p| acc + *p) / samples.len() as f64;
+        assert!((mean - triangle.centroid()).norm() < 0.05);
+    }
+}
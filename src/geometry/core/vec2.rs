@@ -34,6 +34,11 @@ impl Vec2 {
         self.x * other.y - self.y * other.x
     }
 
+    /// Rotate by 90° in positive direction.
+    pub fn perpendicular(self) -> Self {
+        Self::xy(-self.y, self.x)
+    }
+
     pub fn angle(self) -> Angle {
         Angle::rad(self.y.atan2(self.x))
     }
@@ -52,6 +57,13 @@ impl From<(f64, f64)> for Vec2 {
     }
 }
 
+/// x, y
+impl From<Vec2> for [f64; 2] {
+    fn from(Vec2 { x, y }: Vec2) -> Self {
+        [x, y]
+    }
+}
+
 /// radius, angle
 impl From<(f64, Angle)> for Vec2 {
     fn from(rphi: (f64, Angle)) -> Self {
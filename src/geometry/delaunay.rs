@@ -0,0 +1,854 @@
+use crate::geometry::core::*;
+use std::collections::{HashSet, VecDeque};
+
+const EMPTY: usize = usize::MAX;
+
+/// Delaunay triangulation of a point set.
+///
+/// Triangles are stored as a half-edge structure: half-edge `h` starts at
+/// point `triangle_points[h]`, the half-edges `3t`, `3t+1`, `3t+2` form
+/// triangle `t` in positive orientation, and `halfedges[h]` is the opposite
+/// half-edge in the adjacent triangle (or none on the convex hull).
+#[derive(Debug, Clone)]
+pub struct Triangulation {
+    points: Vec<Vec2>,
+    triangle_points: Vec<usize>,
+    halfedges: Vec<usize>,
+    hull: Vec<usize>,
+    constrained: HashSet<(usize, usize)>,
+}
+
+impl Triangulation {
+    /// All input points, including duplicates that are not part of any
+    /// triangle.
+    pub fn points(&self) -> &[Vec2] {
+        &self.points
+    }
+
+    /// Triangles as indices into [`Triangulation::points`], in positive
+    /// orientation.
+    pub fn triangle_indices(&self) -> impl Iterator<Item = [usize; 3]> + '_ {
+        self.triangle_points
+            .chunks_exact(3)
+            .map(|t| [t[0], t[1], t[2]])
+    }
+
+    pub fn triangles(&self) -> impl Iterator<Item = Triangle> + '_ {
+        self.triangle_indices()
+            .map(|[a, b, c]| Triangle::new(self.points[a], self.points[b], self.points[c]))
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.triangle_points.len() / 3
+    }
+
+    /// Each edge once, as pair of point indices.
+    pub fn edge_indices(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0 .. self.halfedges.len())
+            .filter(|&h| self.halfedges[h] == EMPTY || h < self.halfedges[h])
+            .map(|h| (self.triangle_points[h], self.triangle_points[next(h)]))
+    }
+
+    pub fn edges(&self) -> Vec<Line> {
+        self.edge_indices()
+            .map(|(a, b)| Line::from_to(self.points[a], self.points[b]))
+            .collect()
+    }
+
+    /// Convex hull as point indices, in positive orientation.
+    pub fn hull_indices(&self) -> &[usize] {
+        &self.hull
+    }
+
+    /// Check whether an edge was inserted as constraint. Constraints that could
+    /// not be inserted are not part of the triangulation and not reported
+    /// here; constraints through other points are split into edges between
+    /// those points.
+    pub fn is_constrained(&self, a: usize, b: usize) -> bool {
+        self.constrained.contains(&(a.min(b), a.max(b)))
+    }
+}
+
+/// Triangulate a point set so that no point lies inside the circumcircle of
+/// any triangle.
+///
+/// Uses the sweep-hull algorithm also known from the Delaunator library, which
+/// takes O(n log n) time in practice. Duplicate points are skipped. If all
+/// points are collinear, there are no triangles.
+pub fn delaunay(points: &[Vec2]) -> Triangulation {
+    Builder::new(points.to_vec()).triangulation()
+}
+
+/// Delaunay triangulation with the boundary edges of the given polygons forced
+/// to be part of it.
+///
+/// The polygon corners are added to the point set, after the given points.
+/// Triangles are not removed outside of the polygons. Constraint edges should
+/// not cross each other: edges that cannot be inserted, because they cross an
+/// earlier one or in degenerate cases, are left out.
+/// [`Triangulation::is_constrained`] tells which edges made it.
+pub fn constrained_delaunay(points: &[Vec2], boundaries: &[Polygon]) -> Triangulation {
+    let mut all_points = points.to_vec();
+    let mut constraints = Vec::new();
+    for polygon in boundaries {
+        let first = all_points.len();
+        all_points.extend(polygon.points());
+        let n = all_points.len() - first;
+        constraints.extend((0 .. n).map(|i| (first + i, first + (i + 1) % n)));
+    }
+
+    let mut builder = Builder::new(all_points);
+    builder.build_vertex_index();
+    for (a, b) in constraints {
+        let (a, b) = (builder.representative[a], builder.representative[b]);
+        if a != b {
+            builder.insert_constraint(a, b);
+        }
+    }
+    builder.triangulation()
+}
+
+fn next(h: usize) -> usize {
+    match h % 3 {
+        2 => h - 2,
+        _ => h + 1,
+    }
+}
+
+fn prev(h: usize) -> usize {
+    match h % 3 {
+        0 => h + 2,
+        _ => h - 1,
+    }
+}
+
+/// Twice the signed area of the triangle; positive if counter-clockwise.
+fn orient(a: Vec2, b: Vec2, c: Vec2) -> f64 {
+    (b - a).cross(c - a)
+}
+
+/// Whether d lies inside the circumcircle of the positively oriented a, b, c.
+fn in_circle(a: Vec2, b: Vec2, c: Vec2, d: Vec2) -> bool {
+    let (ad, bd, cd) = (a - d, b - d, c - d);
+    let det = ad.norm_square() * bd.cross(cd)
+        + bd.norm_square() * cd.cross(ad)
+        + cd.norm_square() * ad.cross(bd);
+    det > 0.
+}
+
+fn circumradius(a: Vec2, b: Vec2, c: Vec2) -> f64 {
+    Triangle::new(a, b, c).circumcircle().radius
+}
+
+/// Monotonic in the angle of the direction, but cheaper.
+fn pseudo_angle(d: Vec2) -> f64 {
+    let p = d.x / (d.x.abs() + d.y.abs());
+    if d.y > 0. {
+        (3. - p) / 4.
+    } else {
+        (1. + p) / 4.
+    }
+}
+
+struct Builder {
+    points: Vec<Vec2>,
+    triangle_points: Vec<usize>,
+    halfedges: Vec<usize>,
+    /// Index of the point each point was merged into; itself for most points.
+    representative: Vec<usize>,
+    hull_start: usize,
+    hull_prev: Vec<usize>,
+    hull_next: Vec<usize>,
+    hull_tri: Vec<usize>,
+    /// Some half-edge starting at each point, once the triangulation is done.
+    vertex_edge: Vec<usize>,
+    constrained: HashSet<(usize, usize)>,
+}
+
+impl Builder {
+    fn new(points: Vec<Vec2>) -> Self {
+        let n = points.len();
+        let mut builder = Builder {
+            points,
+            triangle_points: Vec::with_capacity(6 * n),
+            halfedges: Vec::with_capacity(6 * n),
+            representative: (0 .. n).collect(),
+            hull_start: EMPTY,
+            hull_prev: vec![EMPTY; n],
+            hull_next: vec![EMPTY; n],
+            hull_tri: vec![EMPTY; n],
+            vertex_edge: Vec::new(),
+            constrained: HashSet::new(),
+        };
+        builder.sweep();
+        builder
+    }
+
+    fn sweep(&mut self) {
+        let points = &self.points;
+        let n = points.len();
+        if n < 3 {
+            return;
+        }
+        let bb: BB = points.iter().map(|p| p.bb()).sum();
+        let center = bb.center();
+        let closest_to = |target: Vec2, exclude: &[usize]| {
+            (0 .. n)
+                .filter(|i| !exclude.contains(i) && points[*i] != target)
+                .min_by(|&i, &j| {
+                    (points[i] - target)
+                        .norm_square()
+                        .total_cmp(&(points[j] - target).norm_square())
+                })
+        };
+
+        // Seed triangle: a central point, its nearest neighbour, and the point
+        // forming the smallest circumcircle with them.
+        let i0 = match (0 .. n).min_by(|&i, &j| {
+            (points[i] - center)
+                .norm_square()
+                .total_cmp(&(points[j] - center).norm_square())
+        }) {
+            Some(i) => i,
+            None => return,
+        };
+        let i1 = match closest_to(points[i0], &[i0]) {
+            Some(i) => i,
+            None => return,
+        };
+        let (p0, p1) = (points[i0], points[i1]);
+        let i2 = (0 .. n)
+            .filter(|&i| i != i0 && i != i1 && orient(p0, p1, points[i]) != 0.)
+            .min_by(|&i, &j| {
+                circumradius(p0, p1, points[i]).total_cmp(&circumradius(p0, p1, points[j]))
+            });
+        let mut i2 = match i2 {
+            Some(i) => i,
+            None => return, // All points are collinear.
+        };
+        let mut i1 = i1;
+        if orient(p0, p1, points[i2]) < 0. {
+            std::mem::swap(&mut i1, &mut i2);
+        }
+        let seed_center = Triangle::new(points[i0], points[i1], points[i2])
+            .circumcircle()
+            .center;
+
+        let mut order: Vec<usize> = (0 .. n).collect();
+        let distances: Vec<f64> = points
+            .iter()
+            .map(|p| (*p - seed_center).norm_square())
+            .collect();
+        order.sort_by(|&i, &j| distances[i].total_cmp(&distances[j]));
+
+        let hash_size = (n as f64).sqrt().ceil() as usize;
+        let mut hash = vec![EMPTY; hash_size];
+        let hash_key = |p: Vec2| {
+            (pseudo_angle(p - seed_center) * hash_size as f64).floor() as usize % hash_size
+        };
+
+        self.hull_start = i0;
+        self.hull_next[i0] = i1;
+        self.hull_prev[i1] = i0;
+        self.hull_next[i1] = i2;
+        self.hull_prev[i2] = i1;
+        self.hull_next[i2] = i0;
+        self.hull_prev[i0] = i2;
+        self.hull_tri[i0] = 0;
+        self.hull_tri[i1] = 1;
+        self.hull_tri[i2] = 2;
+        for i in [i0, i1, i2] {
+            hash[hash_key(self.points[i])] = i;
+        }
+        self.add_triangle(i0, i1, i2, EMPTY, EMPTY, EMPTY);
+
+        // Last point that made it into the triangulation, to detect duplicates.
+        let mut previous: Option<usize> = None;
+        let mut inserted = vec![i0, i1, i2];
+        for &i in &order {
+            let p = self.points[i];
+            if let Some(previous) = previous {
+                if (p - self.points[previous]).norm_square() <= f64::EPSILON * f64::EPSILON {
+                    self.representative[i] = self.representative[previous];
+                    continue;
+                }
+            }
+            if i == i0 || i == i1 || i == i2 {
+                previous = Some(i);
+                continue;
+            }
+
+            // Find a hull edge visible from the point, starting near it.
+            let key = hash_key(p);
+            let mut start = EMPTY;
+            for j in 0 .. hash_size {
+                start = hash[(key + j) % hash_size];
+                if start != EMPTY && start != self.hull_next[start] {
+                    break;
+                }
+            }
+            start = self.hull_prev[start];
+            let mut e = start;
+            loop {
+                let q = self.hull_next[e];
+                if self.visible(i, e, q) {
+                    break;
+                }
+                e = q;
+                if e == start {
+                    e = EMPTY;
+                    break;
+                }
+            }
+            if e == EMPTY {
+                // Numerically inside the hull, most likely a near-duplicate.
+                // The nearest vertex stands in for it; this is rare enough
+                // for a linear search.
+                let nearest = inserted.iter().copied().min_by(|&a, &b| {
+                    (self.points[a] - p)
+                        .norm_square()
+                        .total_cmp(&(self.points[b] - p).norm_square())
+                });
+                if let Some(nearest) = nearest {
+                    self.representative[i] = nearest;
+                }
+                continue;
+            }
+            previous = Some(i);
+            inserted.push(i);
+
+            let t = self.add_triangle(e, i, self.hull_next[e], EMPTY, EMPTY, self.hull_tri[e]);
+            self.hull_tri[i] = self.legalize(t + 2);
+            self.hull_tri[e] = t;
+
+            // Add triangles for further visible edges, forwards …
+            let mut n = self.hull_next[e];
+            loop {
+                let q = self.hull_next[n];
+                if !self.visible(i, n, q) {
+                    break;
+                }
+                let t = self.add_triangle(n, i, q, self.hull_tri[i], EMPTY, self.hull_tri[n]);
+                self.hull_tri[i] = self.legalize(t + 2);
+                self.hull_next[n] = n; // Removed from the hull.
+                n = q;
+            }
+
+            // … and backwards.
+            if e == start {
+                loop {
+                    let q = self.hull_prev[e];
+                    if !self.visible(i, q, e) {
+                        break;
+                    }
+                    let t = self.add_triangle(q, i, e, EMPTY, self.hull_tri[e], self.hull_tri[q]);
+                    self.legalize(t + 2);
+                    self.hull_tri[q] = t;
+                    self.hull_next[e] = e;
+                    e = q;
+                }
+            }
+
+            self.hull_start = e;
+            self.hull_prev[i] = e;
+            self.hull_next[e] = i;
+            self.hull_prev[n] = i;
+            self.hull_next[i] = n;
+            hash[hash_key(p)] = i;
+            hash[hash_key(self.points[e])] = e;
+        }
+    }
+
+    /// Whether hull edge a→b can be seen from point i, i.e. i is to its right.
+    fn visible(&self, i: usize, a: usize, b: usize) -> bool {
+        orient(self.points[a], self.points[b], self.points[i]) < 0.
+    }
+
+    fn add_triangle(
+        &mut self,
+        a: usize,
+        b: usize,
+        c: usize,
+        ha: usize,
+        hb: usize,
+        hc: usize,
+    ) -> usize {
+        let t = self.triangle_points.len();
+        self.triangle_points.extend([a, b, c]);
+        self.halfedges.extend([EMPTY; 3]);
+        self.link(t, ha);
+        self.link(t + 1, hb);
+        self.link(t + 2, hc);
+        t
+    }
+
+    fn link(&mut self, a: usize, b: usize) {
+        self.halfedges[a] = b;
+        if b != EMPTY {
+            self.halfedges[b] = a;
+        }
+    }
+
+    /// Flip edges until the triangles around half-edge `a` are Delaunay.
+    ///
+    /// Returns the half-edge that precedes `a` in the triangle it ends up in,
+    /// which is how the callers keep track of the new hull edges.
+    fn legalize(&mut self, mut a: usize) -> usize {
+        let mut stack = Vec::new();
+        loop {
+            let ar = prev(a);
+            let b = self.halfedges[a];
+            let illegal = b != EMPTY && {
+                let p0 = self.triangle_points[ar];
+                let pr = self.triangle_points[a];
+                let pl = self.triangle_points[next(a)];
+                let p1 = self.triangle_points[prev(b)];
+                in_circle(
+                    self.points[p0],
+                    self.points[pr],
+                    self.points[pl],
+                    self.points[p1],
+                )
+            };
+            if !illegal {
+                match stack.pop() {
+                    Some(next_edge) => {
+                        a = next_edge;
+                        continue;
+                    }
+                    None => return ar,
+                }
+            }
+
+            let bl = prev(b);
+            if self.halfedges[bl] == EMPTY {
+                // The flipped edge was on the hull; update the reference.
+                let mut e = self.hull_start;
+                loop {
+                    if self.hull_tri[e] == bl {
+                        self.hull_tri[e] = a;
+                        break;
+                    }
+                    e = self.hull_prev[e];
+                    if e == self.hull_start {
+                        break;
+                    }
+                }
+            }
+            self.flip(a);
+            stack.push(next(b));
+        }
+    }
+
+    /// Replace the diagonal of the quadrilateral formed by the triangles
+    /// adjacent to half-edge `a`.
+    fn flip(&mut self, a: usize) {
+        let b = self.halfedges[a];
+        let (al, ar) = (next(a), prev(a));
+        let (br, bl) = (next(b), prev(b));
+        let p0 = self.triangle_points[ar];
+        let pr = self.triangle_points[a];
+        let pl = self.triangle_points[al];
+        let p1 = self.triangle_points[bl];
+
+        let hbl = self.halfedges[bl];
+        let har = self.halfedges[ar];
+        self.triangle_points[a] = p1;
+        self.triangle_points[b] = p0;
+        self.link(a, hbl);
+        self.link(b, har);
+        self.link(ar, bl);
+
+        if !self.vertex_edge.is_empty() {
+            self.vertex_edge[p0] = ar;
+            self.vertex_edge[p1] = a;
+            self.vertex_edge[pl] = al;
+            self.vertex_edge[pr] = br;
+        }
+    }
+
+    fn build_vertex_index(&mut self) {
+        self.vertex_edge = vec![EMPTY; self.points.len()];
+        for (h, &p) in self.triangle_points.iter().enumerate() {
+            self.vertex_edge[p] = h;
+        }
+    }
+
+    /// All half-edges starting at a point.
+    fn outgoing(&self, v: usize) -> Vec<usize> {
+        let start = self.vertex_edge[v];
+        if start == EMPTY {
+            return Vec::new();
+        }
+        let mut result = vec![start];
+        let mut h = start;
+        loop {
+            let twin = self.halfedges[prev(h)];
+            if twin == EMPTY || twin == start {
+                break;
+            }
+            result.push(twin);
+            h = twin;
+        }
+        if self.halfedges[prev(h)] == EMPTY {
+            // Hit the hull, so walk the other way round as well.
+            let mut h = start;
+            loop {
+                let twin = self.halfedges[h];
+                if twin == EMPTY {
+                    break;
+                }
+                h = next(twin);
+                result.push(h);
+            }
+        }
+        result
+    }
+
+    fn find_edge(&self, a: usize, b: usize) -> Option<usize> {
+        self.outgoing(a)
+            .into_iter()
+            .find(|&h| self.triangle_points[next(h)] == b)
+    }
+
+    /// Whether a–b is an edge, in either direction.
+    fn has_edge(&self, a: usize, b: usize) -> bool {
+        self.find_edge(a, b).is_some() || self.find_edge(b, a).is_some()
+    }
+
+    /// Whether segments a–b and c–d cross in a single interior point.
+    fn crosses(&self, a: usize, b: usize, c: usize, d: usize) -> bool {
+        let [a, b, c, d] = [a, b, c, d].map(|i| self.points[i]);
+        orient(a, b, c) * orient(a, b, d) < 0. && orient(c, d, a) * orient(c, d, b) < 0.
+    }
+
+    /// Whether point c lies on the open segment a–b.
+    fn on_segment(&self, a: usize, b: usize, c: usize) -> bool {
+        let [a, b, c] = [a, b, c].map(|i| self.points[i]);
+        let ab = b - a;
+        let tolerance = 1e-12 * ab.norm_square();
+        (c - a).cross(ab).abs() <= tolerance && (c - a).dot(ab) > 0. && (c - b).dot(a - b) > 0.
+    }
+
+    /// Force edge a–b into the triangulation (Sloan’s algorithm).
+    fn insert_constraint(&mut self, a: usize, b: usize) {
+        let mut todo = vec![(a, b)];
+        while let Some((a, b)) = todo.pop() {
+            if self.has_edge(a, b) {
+                self.constrained.insert((a.min(b), a.max(b)));
+                continue;
+            }
+            match self.crossed_edges(a, b) {
+                Crossing::Through(c) => {
+                    todo.push((c, b));
+                    todo.push((a, c));
+                }
+                Crossing::Edges(crossed) => self.remove_crossings(a, b, crossed),
+                // The segment leaves the triangulation, which only happens
+                // for degenerate input. The edge stays unconstrained.
+                Crossing::Unreachable => {}
+            }
+        }
+    }
+
+    fn crossed_edges(&self, a: usize, b: usize) -> Crossing {
+        // Find the triangle at a that the segment leaves through.
+        let mut first = None;
+        for h in self.outgoing(a) {
+            let x = self.triangle_points[next(h)];
+            let y = self.triangle_points[prev(h)];
+            for c in [x, y] {
+                if self.on_segment(a, b, c) {
+                    return Crossing::Through(c);
+                }
+            }
+            if self.crosses(a, b, x, y) {
+                first = Some(next(h));
+                break;
+            }
+        }
+        let mut e = match first {
+            Some(e) => e,
+            None => return Crossing::Unreachable,
+        };
+
+        let mut crossed = Vec::new();
+        loop {
+            let (u, v) = (self.triangle_points[e], self.triangle_points[next(e)]);
+            crossed.push((u, v));
+            let twin = self.halfedges[e];
+            if twin == EMPTY {
+                return Crossing::Unreachable;
+            }
+            let z = self.triangle_points[prev(twin)];
+            if z == b {
+                return Crossing::Edges(crossed);
+            }
+            if self.on_segment(a, b, z) {
+                return Crossing::Through(z);
+            }
+            // The twin runs v→u; leave through whichever of its other edges
+            // the segment crosses.
+            e = if self.crosses(a, b, u, z) {
+                next(twin)
+            } else {
+                prev(twin)
+            };
+        }
+    }
+
+    fn remove_crossings(&mut self, a: usize, b: usize, crossed: Vec<(usize, usize)>) {
+        // Flipping an earlier constraint away would break it, so crossing
+        // constraints are left out instead.
+        if crossed
+            .iter()
+            .any(|&(u, v)| self.constrained.contains(&(u.min(v), u.max(v))))
+        {
+            return;
+        }
+        let mut crossed: VecDeque<(usize, usize)> = crossed.into();
+        let mut new_edges = Vec::new();
+        let mut budget = 10 * (crossed.len() + 1) * (crossed.len() + 1);
+        while let Some((u, v)) = crossed.pop_front() {
+            budget = budget.saturating_sub(1);
+            let h = match self.find_edge(u, v) {
+                Some(h) => h,
+                None => continue,
+            };
+            let twin = self.halfedges[h];
+            let p = self.triangle_points[prev(h)];
+            let q = self.triangle_points[prev(twin)];
+            let convex = self.crosses(p, q, u, v);
+            if !convex && budget > 0 {
+                crossed.push_back((u, v));
+                continue;
+            }
+            if !convex {
+                // Out of budget: give up, leaving the edge unconstrained.
+                break;
+            }
+            self.flip(h);
+            if p != a && p != b && q != a && q != b && self.crosses(a, b, p, q) {
+                crossed.push_back((p, q));
+            } else {
+                new_edges.push((p, q));
+            }
+        }
+        if self.has_edge(a, b) {
+            self.constrained.insert((a.min(b), a.max(b)));
+        }
+
+        // Restore the Delaunay property around the new edges.
+        let mut changed = true;
+        let mut rounds = 0;
+        while changed && rounds < 100 {
+            changed = false;
+            rounds += 1;
+            for (p, q) in new_edges.iter_mut() {
+                if self.constrained.contains(&((*p).min(*q), (*p).max(*q))) {
+                    continue;
+                }
+                let h = match self.find_edge(*p, *q) {
+                    Some(h) => h,
+                    None => continue,
+                };
+                let twin = self.halfedges[h];
+                if twin == EMPTY {
+                    continue;
+                }
+                let r = self.triangle_points[prev(h)];
+                let s = self.triangle_points[prev(twin)];
+                let [pp, pq, pr, ps] = [*p, *q, r, s].map(|i| self.points[i]);
+                if in_circle(pp, pq, pr, ps) {
+                    self.flip(h);
+                    (*p, *q) = (r, s);
+                    changed = true;
+                }
+            }
+        }
+    }
+
+    fn triangulation(self) -> Triangulation {
+        let mut hull = Vec::new();
+        if self.hull_start != EMPTY && !self.triangle_points.is_empty() {
+            let mut e = self.hull_start;
+            loop {
+                hull.push(e);
+                e = self.hull_next[e];
+                if e == self.hull_start {
+                    break;
+                }
+            }
+        }
+        Triangulation {
+            points: self.points,
+            triangle_points: self.triangle_points,
+            halfedges: self.halfedges,
+            hull,
+            constrained: self.constrained,
+        }
+    }
+}
+
+enum Crossing {
+    /// The segment runs through another point.
+    Through(usize),
+    /// The segment crosses these edges, in order.
+    Edges(Vec<(usize, usize)>),
+    /// Could not trace the segment, e.g. because an end point was skipped.
+    Unreachable,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::*;
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+    fn random_points(n: usize, seed: u64) -> Vec<Vec2> {
+        let mut rng = SmallRng::seed_from_u64(seed);
+        (0 .. n)
+            .map(|_| Vec2::xy(rng.gen_range(0. .. 1000.), rng.gen_range(0. .. 1000.)))
+            .collect()
+    }
+
+    fn assert_delaunay(triangulation: &Triangulation) {
+        for triangle in triangulation.triangles() {
+            assert_eq!(triangle.orientation(), Orientation::Positive);
+            let Circle { center, radius } = triangle.circumcircle();
+            for p in triangulation.points() {
+                assert!((*p - center).norm() >= radius * (1. - 1e-9));
+            }
+        }
+    }
+
+    #[test]
+    fn square() {
+        let points = vec![
+            Vec2::xy(0., 0.),
+            Vec2::xy(1., 0.),
+            Vec2::xy(1., 1.),
+            Vec2::xy(0., 1.),
+            Vec2::xy(0.5, 0.4),
+        ];
+        let triangulation = delaunay(&points);
+        assert_eq!(triangulation.triangle_count(), 4);
+        assert_eq!(triangulation.edges().len(), 8);
+        assert_eq!(triangulation.hull_indices().len(), 4);
+    }
+
+    #[test]
+    fn empty_circumcircles() {
+        assert_delaunay(&delaunay(&random_points(300, 0)));
+    }
+
+    #[test]
+    fn euler_characteristic() {
+        // For n points with h on the hull: 2n - h - 2 triangles.
+        let n = 10_000;
+        let triangulation = delaunay(&random_points(n, 1));
+        let h = triangulation.hull_indices().len();
+        assert_eq!(triangulation.triangle_count(), 2 * n - h - 2);
+        assert_eq!(triangulation.edges().len(), 3 * n - h - 3);
+    }
+
+    #[test]
+    fn grid_with_duplicates() {
+        let mut points: Vec<Vec2> = (0 .. 100)
+            .map(|i| Vec2::xy((i % 10) as f64, (i / 10) as f64))
+            .collect();
+        points.extend(points.clone());
+        let triangulation = delaunay(&points);
+        assert_eq!(triangulation.triangle_count(), 2 * 9 * 9);
+        let area: f64 = triangulation
+            .triangle_indices()
+            .map(|[a, b, c]| (points[b] - points[a]).cross(points[c] - points[a]) / 2.)
+            .sum();
+        assert!((area - 81.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn collinear() {
+        let points: Vec<Vec2> = (0 .. 10)
+            .map(|i| Vec2::xy(i as f64, 2. * i as f64))
+            .collect();
+        assert_eq!(delaunay(&points).triangle_count(), 0);
+    }
+
+    #[test]
+    fn constraints_are_respected() {
+        let points = random_points(500, 2);
+        let boundary = Polygon::from_points(vec![
+            Vec2::xy(100., 100.),
+            Vec2::xy(900., 150.),
+            Vec2::xy(500., 400.),
+            Vec2::xy(850., 850.),
+            Vec2::xy(150., 800.),
+        ]);
+        let triangulation = constrained_delaunay(&points, &[boundary]);
+        let n = points.len();
+        for i in 0 .. 5 {
+            let (a, b) = (n + i, n + (i + 1) % 5);
+            assert!(triangulation.is_constrained(a, b));
+            assert!(triangulation
+                .edge_indices()
+                .any(|(p, q)| (p, q) == (a, b) || (p, q) == (b, a)));
+        }
+        // Still a valid triangulation of the whole point set.
+        let h = triangulation.hull_indices().len();
+        assert_eq!(triangulation.triangle_count(), 2 * (n + 5) - h - 2);
+    }
+
+    #[test]
+    fn crossing_constraints_are_not_marked_without_edge() {
+        // Crossing constraints are not supported, but whatever is reported as
+        // constrained must be an edge of the triangulation.
+        let points = random_points(200, 3);
+        let square = |x: f64, y: f64| {
+            Polygon::from_points(vec![
+                Vec2::xy(x, y),
+                Vec2::xy(x + 400., y),
+                Vec2::xy(x + 400., y + 400.),
+                Vec2::xy(x, y + 400.),
+            ])
+        };
+        let polygons = [square(200., 200.), square(350., 330.)];
+        let triangulation = constrained_delaunay(&points, &polygons);
+        let n = points.len();
+        let edges: Vec<(usize, usize)> = (0 .. 8)
+            .map(|i| (n + i, n + i / 4 * 4 + (i + 1) % 4))
+            .collect();
+        assert!(!edges
+            .iter()
+            .all(|&(a, b)| triangulation.is_constrained(a, b)));
+        for (a, b) in edges {
+            if triangulation.is_constrained(a, b) {
+                assert!(triangulation
+                    .edge_indices()
+                    .any(|(p, q)| (p, q) == (a, b) || (p, q) == (b, a)));
+            }
+        }
+    }
+
+    #[test]
+    fn duplicates_of_skipped_points_stay_usable() {
+        // The second copy of a corner is not next to the first in the sweep
+        // order, so it is only noticed as lying inside the hull. The third
+        // copy follows the second directly.
+        let corner = Vec2::xy(0., 0.);
+        let points = vec![
+            corner,
+            Vec2::xy(10., 0.),
+            Vec2::xy(10., 10.),
+            Vec2::xy(0., 10.),
+            Vec2::xy(5., 5.),
+            corner,
+        ];
+        let triangle = Polygon::from_points(vec![corner, Vec2::xy(10., 0.), Vec2::xy(5., 5.)]);
+        let triangulation = constrained_delaunay(&points, &[triangle]);
+        let at = |p: Vec2| triangulation.points().iter().position(|q| *q == p).unwrap();
+        let (a, b, c) = (at(corner), at(Vec2::xy(10., 0.)), at(Vec2::xy(5., 5.)));
+        assert!(triangulation.is_constrained(a, b));
+        assert!(triangulation.is_constrained(c, a));
+        assert!(triangulation.is_constrained(b, c));
+    }
+}
@@ -0,0 +1,438 @@
+use crate::geometry::core::*;
+
+/// Split an area into triangles by ear clipping.
+///
+/// The triangles have the orientation of the outer ring they belong to, and
+/// holes may be oriented either way. Indices refer to the corners of all
+/// rings, outer ring first, then the holes in order.
+pub trait Triangulate {
+    fn triangulate_indices(&self) -> Vec<[usize; 3]>;
+
+    fn triangulate(&self) -> Vec<Triangle>;
+}
+
+impl Triangulate for Polygon {
+    fn triangulate_indices(&self) -> Vec<[usize; 3]> {
+        ear_clipping(self, &[])
+    }
+
+    fn triangulate(&self) -> Vec<Triangle> {
+        to_triangles(std::slice::from_ref(self), self.triangulate_indices())
+    }
+}
+
+impl Triangulate for PolygonWithHoles {
+    fn triangulate_indices(&self) -> Vec<[usize; 3]> {
+        ear_clipping(&self.outer, &self.holes)
+    }
+
+    fn triangulate(&self) -> Vec<Triangle> {
+        let rings: Vec<Polygon> = self.rings().cloned().collect();
+        to_triangles(&rings, self.triangulate_indices())
+    }
+}
+
+impl Triangulate for MultiPolygon {
+    fn triangulate_indices(&self) -> Vec<[usize; 3]> {
+        let mut offset = 0;
+        let mut result = Vec::new();
+        for part in self.parts() {
+            result.extend(
+                part.triangulate_indices()
+                    .into_iter()
+                    .map(|t| t.map(|i| i + offset)),
+            );
+            offset += part
+                .rings()
+                .map(|ring| ring.points().count())
+                .sum::<usize>();
+        }
+        result
+    }
+
+    fn triangulate(&self) -> Vec<Triangle> {
+        self.parts().flat_map(|part| part.triangulate()).collect()
+    }
+}
+
+fn to_triangles(rings: &[Polygon], indices: Vec<[usize; 3]>) -> Vec<Triangle> {
+    let points: Vec<Vec2> = rings
+        .iter()
+        .flat_map(|ring| ring.points().copied())
+        .collect();
+    indices
+        .into_iter()
+        .map(|[a, b, c]| Triangle::new(points[a], points[b], points[c]))
+        .collect()
+}
+
+/// Twice the signed area of the triangle; positive if counter-clockwise.
+fn orient(a: Vec2, b: Vec2, c: Vec2) -> f64 {
+    (b - a).cross(c - a)
+}
+
+fn ear_clipping(outer: &Polygon, holes: &[Polygon]) -> Vec<[usize; 3]> {
+    let mut ring = Ring::default();
+    let outer_orientation = outer.orientation();
+    let Some(start) = ring.add(outer, 0, Orientation::Positive) else {
+        return Vec::new();
+    };
+
+    // Link the holes into the outer ring, left to right, each with a pair of
+    // bridge edges to a vertex further left.
+    let mut offset = outer.points().count();
+    let mut hole_starts = Vec::new();
+    for hole in holes {
+        if let Some(leftmost) = ring.add(hole, offset, Orientation::Negative) {
+            hole_starts.push(leftmost);
+        }
+        offset += hole.points().count();
+    }
+    hole_starts.sort_by(|&a, &b| ring.position[a].x.total_cmp(&ring.position[b].x));
+    for hole in hole_starts {
+        ring.bridge(hole, start);
+    }
+
+    let mut triangles = ring.clip_ears(start);
+    if outer_orientation == Orientation::Negative {
+        for t in &mut triangles {
+            t.swap(1, 2);
+        }
+    }
+    triangles
+}
+
+/// Doubly linked list of ring corners.
+#[derive(Default)]
+struct Ring {
+    index: Vec<usize>,
+    position: Vec<Vec2>,
+    prev: Vec<usize>,
+    next: Vec<usize>,
+}
+
+impl Ring {
+    /// Add a closed ring with the given orientation, and return its leftmost
+    /// node.
+    fn add(&mut self, polygon: &Polygon, offset: usize, orientation: Orientation) -> Option<usize> {
+        let points: Vec<Vec2> = polygon.points().copied().collect();
+        if points.len() < 3 {
+            return None;
+        }
+        let first = self.index.len();
+        let n = points.len();
+        let mut order: Vec<usize> = (0 .. n).collect();
+        if polygon.orientation() != orientation {
+            order.reverse();
+        }
+        for (k, &i) in order.iter().enumerate() {
+            self.index.push(offset + i);
+            self.position.push(points[i]);
+            self.prev.push(first + (k + n - 1) % n);
+            self.next.push(first + (k + 1) % n);
+        }
+        (first .. first + n).min_by(|&a, &b| {
+            let (p, q) = (self.position[a], self.position[b]);
+            p.x.total_cmp(&q.x).then(p.y.total_cmp(&q.y))
+        })
+    }
+
+    fn nodes(&self, start: usize) -> impl Iterator<Item = usize> + '_ {
+        let mut node = start;
+        let mut done = false;
+        std::iter::from_fn(move || {
+            if done {
+                return None;
+            }
+            let current = node;
+            node = self.next[node];
+            done = node == start;
+            Some(current)
+        })
+    }
+
+    fn corner(&self, node: usize) -> (Vec2, Vec2, Vec2) {
+        (
+            self.position[self.prev[node]],
+            self.position[node],
+            self.position[self.next[node]],
+        )
+    }
+
+    fn is_reflex(&self, node: usize) -> bool {
+        let (a, b, c) = self.corner(node);
+        orient(a, b, c) < 0.
+    }
+
+    /// Whether the direction from the node towards the target points into
+    /// the area.
+    fn locally_inside(&self, node: usize, target: Vec2) -> bool {
+        let (a, b, c) = self.corner(node);
+        if orient(a, b, c) >= 0. {
+            orient(a, b, target) >= 0. && orient(b, c, target) >= 0.
+        } else {
+            orient(a, b, target) >= 0. || orient(b, c, target) >= 0.
+        }
+    }
+
+    /// Connect the hole (given by its leftmost node) with the ring that
+    /// contains `start`, by a ray going left.
+    fn bridge(&mut self, hole: usize, start: usize) {
+        let m = self.position[hole];
+
+        // Closest edge crossing the ray; it runs downwards, with the area on
+        // its right-hand side from the hole’s point of view.
+        let mut best: Option<(f64, usize)> = None;
+        for node in self.nodes(start) {
+            let (p, q) = (self.position[node], self.position[self.next[node]]);
+            if p.y >= m.y && m.y >= q.y && p.y != q.y {
+                let x = p.x + (m.y - p.y) / (q.y - p.y) * (q.x - p.x);
+                if x <= m.x && best.is_none_or(|(best_x, _)| x > best_x) {
+                    let endpoint = if p.x < q.x { node } else { self.next[node] };
+                    best = Some((x, endpoint));
+                }
+            }
+        }
+        let Some((x, mut target)) = best else {
+            return;
+        };
+
+        // Vertices inside the triangle between hole, ray hit and edge end
+        // point would block the bridge; take the one closest in angle.
+        let hit = Vec2::xy(x, m.y);
+        if hit != self.position[target] {
+            let triangle = Triangle::new(m, hit, self.position[target]);
+            let tangent = |p: Vec2| (m.y - p.y).abs() / (m.x - p.x);
+            let mut best_tangent = tangent(self.position[target]);
+            for node in self.nodes(start) {
+                let p = self.position[node];
+                if node == target
+                    || p.x >= m.x
+                    || !triangle.contains(p)
+                    || !self.locally_inside(node, m)
+                {
+                    continue;
+                }
+                let t = tangent(p);
+                let closer = (t, -p.x) < (best_tangent, -self.position[target].x);
+                if closer {
+                    target = node;
+                    best_tangent = t;
+                }
+            }
+        }
+
+        // Splice: target → hole … around the hole … hole' → target' → rest.
+        let hole_copy = self.duplicate(hole);
+        let target_copy = self.duplicate(target);
+        let (hole_prev, target_next) = (self.prev[hole], self.next[target]);
+        self.link(target, hole);
+        self.link(hole_prev, hole_copy);
+        self.link(hole_copy, target_copy);
+        self.link(target_copy, target_next);
+    }
+
+    fn duplicate(&mut self, node: usize) -> usize {
+        self.index.push(self.index[node]);
+        self.position.push(self.position[node]);
+        self.prev.push(usize::MAX);
+        self.next.push(usize::MAX);
+        self.index.len() - 1
+    }
+
+    fn link(&mut self, a: usize, b: usize) {
+        self.next[a] = b;
+        self.prev[b] = a;
+    }
+
+    fn remove(&mut self, node: usize) {
+        self.link(self.prev[node], self.next[node]);
+    }
+
+    fn is_ear(&self, node: usize) -> bool {
+        let (a, b, c) = self.corner(node);
+        if orient(a, b, c) <= 0. {
+            return false;
+        }
+        let triangle = Triangle::new(a, b, c);
+        let mut other = self.next[self.next[node]];
+        while other != self.prev[node] {
+            let p = self.position[other];
+            if p != a && p != b && p != c && self.is_reflex(other) && triangle.contains(p) {
+                return false;
+            }
+            other = self.next[other];
+        }
+        true
+    }
+
+    fn clip_ears(&mut self, start: usize) -> Vec<[usize; 3]> {
+        let mut remaining = self.nodes(start).count();
+        let mut triangles = Vec::with_capacity(remaining.saturating_sub(2));
+        let mut node = start;
+        let mut stop = node;
+        let mut stuck = false;
+        while remaining > 3 {
+            let (prev, next) = (self.prev[node], self.next[node]);
+            // Normally only proper ears are clipped. If there are none left,
+            // drop degenerate corners first, and then clip whatever is convex,
+            // which only happens for self-intersecting input.
+            let (a, b, c) = self.corner(node);
+            let degenerate = stuck && orient(a, b, c) == 0.;
+            if self.is_ear(node) || degenerate || (stuck && orient(a, b, c) > 0.) {
+                if !degenerate {
+                    triangles.push([self.index[prev], self.index[node], self.index[next]]);
+                }
+                self.remove(node);
+                remaining -= 1;
+                node = next;
+                stop = next;
+                stuck = false;
+                continue;
+            }
+            node = next;
+            if node == stop {
+                if stuck {
+                    // Not a single convex corner: give up on the rest.
+                    break;
+                }
+                stuck = true;
+            }
+        }
+        if remaining == 3 {
+            let (prev, next) = (self.prev[node], self.next[node]);
+            let (a, b, c) = self.corner(node);
+            if orient(a, b, c) > 0. {
+                triangles.push([self.index[prev], self.index[node], self.index[next]]);
+            }
+        }
+        triangles
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::*;
+    use std::f64::consts::PI;
+
+    fn square(min: f64, max: f64) -> Polygon {
+        Polygon::from_points(vec![
+            Vec2::xy(min, min),
+            Vec2::xy(max, min),
+            Vec2::xy(max, max),
+            Vec2::xy(min, max),
+        ])
+    }
+
+    fn reversed(polygon: &Polygon) -> Polygon {
+        let mut points: Vec<Vec2> = polygon.points().copied().collect();
+        points.reverse();
+        Polygon::from_points(points)
+    }
+
+    fn total_area(triangles: &[Triangle]) -> f64 {
+        triangles.iter().map(|t| t.area()).sum()
+    }
+
+    /// Star with spikes of alternating radius, very concave.
+    fn star(n: usize) -> Polygon {
+        Polygon::from_points(
+            (0 .. 2 * n)
+                .map(|i| {
+                    let r = if i % 2 == 0 {
+                        100.
+                    } else {
+                        30. + (i % 7) as f64
+                    };
+                    Vec2::polar(r, Angle::rad(PI * i as f64 / n as f64))
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn convex() {
+        let triangles = square(0., 10.).triangulate();
+        assert_eq!(triangles.len(), 2);
+        assert!((total_area(&triangles) - 100.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn concave_star() {
+        let star = star(50);
+        let triangles = star.triangulate();
+        assert_eq!(triangles.len(), 98);
+        assert!((total_area(&triangles) - star.area()).abs() < 1e-6);
+        for triangle in triangles {
+            assert_eq!(triangle.orientation(), Orientation::Positive);
+            assert!(star.contains(triangle.centroid()));
+        }
+    }
+
+    #[test]
+    fn respects_orientation() {
+        let star = reversed(&star(20));
+        let triangles = star.triangulate();
+        assert!((total_area(&triangles) - star.area()).abs() < 1e-6);
+        assert!(triangles
+            .iter()
+            .all(|t| t.orientation() == Orientation::Negative));
+    }
+
+    #[test]
+    fn holes() {
+        for hole in [square(3., 7.), reversed(&square(3., 7.))] {
+            let frame = PolygonWithHoles::new(square(0., 10.), vec![hole]);
+            let triangles = frame.triangulate();
+            // n + 2h - 2 triangles for n corners and h holes.
+            assert_eq!(triangles.len(), 8);
+            assert!((total_area(&triangles) - 84.).abs() < 1e-9);
+            for triangle in triangles {
+                assert!(!Polygon::from(triangle).contains(Vec2::xy(5., 5.)));
+            }
+        }
+    }
+
+    #[test]
+    fn many_holes() {
+        let holes: Vec<Polygon> = (0 .. 4)
+            .flat_map(|i| (0 .. 4).map(move |j| (i, j)))
+            .map(|(i, j)| {
+                // Staggered, so no bridge runs through other corners.
+                let (x, y) = (i as f64 * 25. + 10., j as f64 * 25. + i as f64 + 10.);
+                Polygon::from_points(vec![
+                    Vec2::xy(x, y),
+                    Vec2::xy(x + 5., y),
+                    Vec2::xy(x + 2., y + 5.),
+                ])
+            })
+            .collect();
+        let shape = PolygonWithHoles::new(square(0., 100.), holes);
+        let triangles = shape.triangulate();
+        // At most n + 2h - 2, minus degenerate ones at shared bridge ends.
+        assert!(triangles.len() <= 4 + 16 * 3 + 2 * 16 - 2);
+        assert!((total_area(&triangles) - shape.area()).abs() < 1e-6);
+        assert!(triangles
+            .iter()
+            .all(|t| t.orientation() == Orientation::Positive));
+    }
+
+    #[test]
+    fn indices_cover_all_rings() {
+        let frame = square(0., 10.).difference(&square(3., 7.));
+        let indices = frame.triangulate_indices();
+        let points: Vec<Vec2> = frame
+            .rings()
+            .flat_map(|ring| ring.points().copied())
+            .collect();
+        assert_eq!(points.len(), 8);
+        for i in 0 .. points.len() {
+            assert!(indices.iter().any(|t| t.contains(&i)));
+        }
+        let area: f64 = indices
+            .iter()
+            .map(|&[a, b, c]| Triangle::new(points[a], points[b], points[c]).area())
+            .sum();
+        assert!((area - 84.).abs() < 1e-9);
+    }
+}
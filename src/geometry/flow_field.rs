@@ -0,0 +1,619 @@
+use crate::geometry::core::*;
+use crate::geometry::spatial_index::*;
+use crate::numerics::noise::*;
+use std::f64::consts::TAU;
+
+/// Direction at every point of the plane, for example the flow of a liquid.
+pub trait VectorField {
+    fn at(&self, point: Vec2) -> Vec2;
+}
+
+impl<F: Fn(Vec2) -> Vec2> VectorField for F {
+    fn at(&self, point: Vec2) -> Vec2 {
+        self(point)
+    }
+}
+
+/// Unit vectors whose angle follows a 2D noise: the classic flow field.
+///
+/// The noise range [-1, 1] maps to angles of one full turn in either direction.
+pub struct NoiseField<S> {
+    noise: S,
+    frequency: f64,
+}
+
+impl<S: Noise<2>> NoiseField<S> {
+    /// The noise is sampled at the point times the frequency, so larger
+    /// frequencies give more turbulent flow.
+    pub fn new(noise: S, frequency: f64) -> Self {
+        NoiseField { noise, frequency }
+    }
+}
+
+impl<S: Noise<2>> VectorField for NoiseField<S> {
+    fn at(&self, point: Vec2) -> Vec2 {
+        let angle = self.noise.get((point * self.frequency).into()) * TAU;
+        Vec2::polar(1., Angle::rad(angle))
+    }
+}
+
+/// Vectors given on a regular grid spanning a bounding box, bilinearly
+/// interpolated in between. Outside of the box, the nearest edge value is
+/// used.
+pub struct GridField {
+    bb: BB,
+    size_x: usize,
+    size_y: usize,
+    vectors: Vec<Vec2>,
+}
+
+impl GridField {
+    /// Grid with `size_x` × `size_y` vectors in row-major order, the first one
+    /// at the minimum corner of the box and the last one at the maximum.
+    ///
+    /// Panics unless there are at least two vectors along each axis, and
+    /// exactly `size_x * size_y` in total.
+    pub fn new(bb: BB, size_x: usize, size_y: usize, vectors: Vec<Vec2>) -> Self {
+        assert!(
+            size_x >= 2 && size_y >= 2,
+            "grid needs at least 2×2 vectors"
+        );
+        assert_eq!(vectors.len(), size_x * size_y, "wrong number of vectors");
+        GridField {
+            bb,
+            size_x,
+            size_y,
+            vectors,
+        }
+    }
+
+    /// Grid sampling another vector field, to freeze an expensive field.
+    pub fn from_field(bb: BB, size_x: usize, size_y: usize, field: impl VectorField) -> Self {
+        let (min, extent) = (bb.min(), bb.max() - bb.min());
+        let vectors = (0 .. size_y)
+            .flat_map(|iy| (0 .. size_x).map(move |ix| (ix, iy)))
+            .map(|(ix, iy)| {
+                field.at(Vec2::xy(
+                    min.x + extent.x * ix as f64 / (size_x - 1) as f64,
+                    min.y + extent.y * iy as f64 / (size_y - 1) as f64,
+                ))
+            })
+            .collect();
+        Self::new(bb, size_x, size_y, vectors)
+    }
+}
+
+impl VectorField for GridField {
+    fn at(&self, point: Vec2) -> Vec2 {
+        let (min, extent) = (self.bb.min(), self.bb.max() - self.bb.min());
+        // Position in units of grid cells, clamped to the grid.
+        let gx = ((point.x - min.x) / extent.x * (self.size_x - 1) as f64)
+            .clamp(0., (self.size_x - 1) as f64);
+        let gy = ((point.y - min.y) / extent.y * (self.size_y - 1) as f64)
+            .clamp(0., (self.size_y - 1) as f64);
+        let (ix, iy) = (
+            (gx as usize).min(self.size_x - 2),
+            (gy as usize).min(self.size_y - 2),
+        );
+        let (tx, ty) = (gx - ix as f64, gy - iy as f64);
+
+        let vector = |ix: usize, iy: usize| self.vectors[iy * self.size_x + ix];
+        let bottom = vector(ix, iy) * (1. - tx) + vector(ix + 1, iy) * tx;
+        let top = vector(ix, iy + 1) * (1. - tx) + vector(ix + 1, iy + 1) * tx;
+        bottom * (1. - ty) + top * ty
+    }
+}
+
+/// Streamlines of a vector field: curves that follow the direction of the
+/// field, traced with fourth-order Runge–Kutta steps.
+///
+/// Lines stop when they leave the region, reach a point where the field
+/// vanishes, or after the maximum number of steps. With a separation, they
+/// also stop when they come closer than that to an earlier line, and seeds
+/// too close to earlier lines are skipped.
+pub struct Streamlines<F> {
+    field: F,
+    region: BB,
+    step: f64,
+    max_steps: usize,
+    separation: Option<f64>,
+    bidirectional: bool,
+}
+
+impl<F: VectorField> Streamlines<F> {
+    pub fn new(field: F, region: BB) -> Self {
+        Streamlines {
+            field,
+            region,
+            step: 1.,
+            max_steps: 1000,
+            separation: None,
+            bidirectional: false,
+        }
+    }
+
+    /// Distance between consecutive points of a line. Panics unless positive.
+    pub fn step(mut self, step: f64) -> Self {
+        assert!(step > 0., "step must be positive");
+        self.step = step;
+        self
+    }
+
+    /// Maximum number of steps in each direction.
+    pub fn max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    /// Minimum distance to earlier lines. Panics unless positive.
+    pub fn separation(mut self, separation: f64) -> Self {
+        assert!(separation > 0., "separation must be positive");
+        self.separation = Some(separation);
+        self
+    }
+
+    /// Trace both downstream and upstream from the seeds, so the seeds end up
+    /// in the middle of their lines.
+    pub fn bidirectional(mut self, bidirectional: bool) -> Self {
+        self.bidirectional = bidirectional;
+        self
+    }
+
+    /// Trace a line from each seed, for example from the points of a
+    /// [`crate::geometry::poisson_disc`]. Lines with fewer than two points are
+    /// dropped; the rest can be drawn with `Sketch<&[Vec2]>`.
+    pub fn trace(&self, seeds: impl IntoIterator<Item = Vec2>) -> Vec<Vec<Vec2>> {
+        let mut taken = self.separation.map(SpatialIndex::new);
+        let mut lines = Vec::new();
+        for seed in seeds {
+            if !self.region.contains(seed) || self.crowded(&taken, seed) {
+                continue;
+            }
+            let mut line = self.trace_one(seed, 1., |p| self.crowded(&taken, p));
+            if self.bidirectional {
+                let mut backwards = self.trace_one(seed, -1., |p| self.crowded(&taken, p));
+                backwards.reverse();
+                backwards.pop();
+                backwards.append(&mut line);
+                line = backwards;
+            }
+            if line.len() < 2 {
+                continue;
+            }
+            if let Some(index) = &mut taken {
+                index.extend(line.iter().copied());
+            }
+            lines.push(line);
+        }
+        lines
+    }
+
+    /// Whether a point is too close to the points of earlier lines.
+    fn crowded(&self, taken: &Option<SpatialIndex<Vec2>>, point: Vec2) -> bool {
+        match (taken, self.separation) {
+            (Some(taken), Some(separation)) => {
+                taken.within_radius(point, separation).next().is_some()
+            }
+            _ => false,
+        }
+    }
+
+    /// Line from the seed in the direction of the field, or against it for
+    /// a negative sign.
+    fn trace_one(&self, seed: Vec2, sign: f64, crowded: impl Fn(Vec2) -> bool) -> Vec<Vec2> {
+        let mut line = vec![seed];
+        let mut point = seed;
+        for _ in 0 .. self.max_steps {
+            let next = match rk4_step(&self.field, point, self.step, sign) {
+                Some(next) => next,
+                None => break,
+            };
+            if !self.region.contains(next) || crowded(next) {
+                break;
+            }
+            line.push(next);
+            point = next;
+        }
+        line
+    }
+}
+
+/// Evenly spaced streamlines (Jobard and Lefer 1997): lines that fill the
+/// region while keeping about the separation distance from each other, like
+/// the spacing [`crate::geometry::poisson_disc`] guarantees for points.
+///
+/// Each new line starts at the separation distance beside a point of an
+/// earlier line, and stops when it comes closer than `test_ratio` times the
+/// separation to any line, including itself. Areas that cannot be reached
+/// this way, for example behind places where the field vanishes, are seeded
+/// from a lattice.
+pub struct EvenlySpacedStreamlines<F, Region> {
+    field: F,
+    region: Region,
+    separation: f64,
+    test_ratio: f64,
+    step: f64,
+    max_steps: usize,
+    seed: Option<Vec2>,
+}
+
+/// Position of a point of a line: the line’s index, and the number of steps
+/// from its seed, negative upstream.
+type LinePoint = (Vec2, (usize, i64));
+
+impl<F: VectorField, Region: HasBB + Contains> EvenlySpacedStreamlines<F, Region> {
+    /// Lines `separation` apart. Panics unless it is positive.
+    pub fn new(field: F, region: Region, separation: f64) -> Self {
+        assert!(separation > 0., "separation must be positive");
+        EvenlySpacedStreamlines {
+            field,
+            region,
+            separation,
+            test_ratio: 0.5,
+            step: separation / 5.,
+            max_steps: 10_000,
+            seed: None,
+        }
+    }
+
+    /// How close lines may come, relative to the separation. Smaller values
+    /// give longer lines that bunch up more where the flow converges. Panics
+    /// unless it is in (0, 1].
+    pub fn test_ratio(mut self, test_ratio: f64) -> Self {
+        assert!(
+            test_ratio > 0. && test_ratio <= 1.,
+            "test ratio must be in (0, 1]"
+        );
+        self.test_ratio = test_ratio;
+        self
+    }
+
+    /// Distance between consecutive points of a line. Panics unless positive.
+    pub fn step(mut self, step: f64) -> Self {
+        assert!(step > 0., "step must be positive");
+        self.step = step;
+        self
+    }
+
+    /// Maximum number of steps in each direction, so that lines which never
+    /// crowd themselves still end.
+    pub fn max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    /// Start of the first line, by default the center of the region.
+    pub fn seed(mut self, seed: Vec2) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn generate(&self) -> Vec<Vec<Vec2>> {
+        let bb = self.region.bb();
+        let mut taken: SpatialIndex<LinePoint> = SpatialIndex::new(self.separation);
+        let mut lines = Vec::new();
+
+        let (min, extent) = (bb.min(), bb.max() - bb.min());
+        let (size_x, size_y) = (
+            (extent.x / self.separation).ceil() as usize,
+            (extent.y / self.separation).ceil() as usize,
+        );
+        let mut lattice = (0 .. size_y)
+            .flat_map(|iy| (0 .. size_x).map(move |ix| (ix, iy)))
+            .map(|(ix, iy)| min + Vec2::xy(ix as f64 + 0.5, iy as f64 + 0.5) * self.separation);
+
+        self.try_line(self.seed.unwrap_or(bb.center()), &mut taken, &mut lines);
+        let mut next_source = 0;
+        loop {
+            // Seed new lines beside the points of the existing ones, in the
+            // order the lines were found.
+            while let Some(source) = lines.get(next_source) {
+                let candidates: Vec<Vec2> = source
+                    .windows(2)
+                    .flat_map(|pair| {
+                        let normal = (pair[1] - pair[0]).perpendicular();
+                        let offset = normal * (self.separation / normal.norm());
+                        [pair[0] + offset, pair[0] - offset]
+                    })
+                    .collect();
+                for candidate in candidates {
+                    self.try_line(candidate, &mut taken, &mut lines);
+                }
+                next_source += 1;
+            }
+            if !lattice.any(|seed| self.try_line(seed, &mut taken, &mut lines)) {
+                break;
+            }
+        }
+        lines
+    }
+
+    /// Trace a line from the seed in both directions, if the seed is far
+    /// enough from all other lines.
+    fn try_line(
+        &self,
+        seed: Vec2,
+        taken: &mut SpatialIndex<LinePoint>,
+        lines: &mut Vec<Vec<Vec2>>,
+    ) -> bool {
+        // Seeds beside a line are exactly the separation away from it, up to
+        // rounding.
+        let free_radius = self.separation * (1. - 1e-9);
+        if !self.region.contains(seed) || taken.within_radius(seed, free_radius).next().is_some() {
+            return false;
+        }
+        let line_index = lines.len();
+        let mut ids = vec![taken.insert((seed, (line_index, 0)))];
+        let mut halves = [Vec::new(), Vec::new()];
+        for (half, sign) in halves.iter_mut().zip([1_i64, -1]) {
+            let mut point = seed;
+            for steps in 1 ..= self.max_steps as i64 {
+                let next = match rk4_step(&self.field, point, self.step, sign as f64) {
+                    Some(next) => next,
+                    None => break,
+                };
+                if !self.region.contains(next)
+                    || self.crowded(taken, next, line_index, sign * steps)
+                {
+                    break;
+                }
+                ids.push(taken.insert((next, (line_index, sign * steps))));
+                half.push(next);
+                point = next;
+            }
+        }
+
+        let [downstream, mut upstream] = halves;
+        if downstream.is_empty() && upstream.is_empty() {
+            for id in ids {
+                taken.remove(id);
+            }
+            return false;
+        }
+        upstream.reverse();
+        upstream.push(seed);
+        upstream.extend(downstream);
+        lines.push(upstream);
+        true
+    }
+
+    /// Whether a point of a line is too close to another line, or to a part of
+    /// its own line that is not just next to it.
+    fn crowded(
+        &self,
+        taken: &SpatialIndex<LinePoint>,
+        point: Vec2,
+        line: usize,
+        steps: i64,
+    ) -> bool {
+        let distance = self.test_ratio * self.separation;
+        // Points this many steps apart along the line are clearly apart, even
+        // if the line curves.
+        let neighbourhood = (2. * distance / self.step).ceil() as i64;
+        taken
+            .within_radius(point, distance)
+            .any(|(_, (_, (other_line, other_steps)))| {
+                *other_line != line || (other_steps - steps).abs() > neighbourhood
+            })
+    }
+}
+
+/// One step along the normalized field, so that steps have the same length
+/// regardless of the field’s magnitude, against the field for a negative
+/// sign. None where the field vanishes.
+fn rk4_step(field: &impl VectorField, point: Vec2, step: f64, sign: f64) -> Option<Vec2> {
+    let direction = |p: Vec2| {
+        let v = field.at(p);
+        let norm = v.norm();
+        (norm > 1e-12 && norm.is_finite()).then(|| v * (sign / norm))
+    };
+    let h = step;
+    let k1 = direction(point)?;
+    let k2 = direction(point + k1 * (h / 2.))?;
+    let k3 = direction(point + k2 * (h / 2.))?;
+    let k4 = direction(point + k3 * h)?;
+    Some(point + (k1 + k2 * 2. + k3 * 2. + k4) * (h / 6.))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::*;
+    use crate::numerics::noise::*;
+    use rand::{rngs::SmallRng, SeedableRng};
+
+    fn region() -> BB {
+        (Vec2::xy(0., 0.), Vec2::xy(100., 100.)).bb()
+    }
+
+    #[test]
+    fn uniform_field_gives_straight_lines_to_the_edge() {
+        let right = |_| Vec2::xy(3., 0.);
+        let lines = Streamlines::new(right, region())
+            .step(2.)
+            .trace([Vec2::xy(10., 50.)]);
+        assert_eq!(lines.len(), 1);
+        let line = &lines[0];
+        assert_eq!(line.len(), 46);
+        assert!(line.iter().all(|p| (p.y - 50.).abs() < 1e-12));
+        assert!((line.last().unwrap().x - 100.).abs() <= 2.);
+    }
+
+    #[test]
+    fn circular_field_keeps_the_radius() {
+        let center = Vec2::xy(50., 50.);
+        let circular = move |p: Vec2| (p - center).perpendicular();
+        let lines = Streamlines::new(circular, region())
+            .step(0.5)
+            .max_steps(2000)
+            .trace([Vec2::xy(80., 50.)]);
+        assert_eq!(lines[0].len(), 2001);
+        assert!(lines[0]
+            .iter()
+            .all(|p| ((*p - center).norm() - 30.).abs() < 1e-3));
+    }
+
+    #[test]
+    fn stops_where_the_field_vanishes() {
+        let calm_right_half = |p: Vec2| {
+            if p.x < 50. {
+                Vec2::xy(1., 0.)
+            } else {
+                Vec2::xy(0., 0.)
+            }
+        };
+        let lines = Streamlines::new(calm_right_half, region()).trace([Vec2::xy(20., 50.)]);
+        let end = lines[0].last().unwrap().x;
+        assert!((49. .. 50.).contains(&end), "{end}");
+    }
+
+    #[test]
+    fn separated_lines_from_poisson_seeds() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let seeds = poisson_disc(&mut rng, region(), 8., 20);
+        let field = NoiseField::new(Perlin::seeded(0), 0.02);
+        let separation = 3.;
+        let lines = Streamlines::new(field, region())
+            .separation(separation)
+            .bidirectional(true)
+            .trace(seeds);
+        assert!(lines.len() > 20);
+        for (i, a) in lines.iter().enumerate() {
+            assert!(a.iter().all(|p| region().contains(*p)));
+            for b in &lines[.. i] {
+                for p in a {
+                    assert!(b.iter().all(|q| (*p - *q).norm() >= separation));
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "step must be positive")]
+    fn streamlines_reject_zero_step() {
+        Streamlines::new(|_| Vec2::xy(1., 0.), region()).step(0.);
+    }
+
+    #[test]
+    #[should_panic(expected = "separation must be positive")]
+    fn streamlines_reject_negative_separation() {
+        Streamlines::new(|_| Vec2::xy(1., 0.), region()).separation(-1.);
+    }
+
+    #[test]
+    fn grid_field_interpolates_bilinearly() {
+        let vectors = vec![
+            Vec2::xy(0., 0.),
+            Vec2::xy(2., 0.),
+            Vec2::xy(0., 2.),
+            Vec2::xy(2., 2.),
+        ];
+        let grid = GridField::new(region(), 2, 2, vectors);
+        assert_eq!(grid.at(Vec2::xy(0., 0.)), Vec2::xy(0., 0.));
+        assert_eq!(grid.at(Vec2::xy(100., 100.)), Vec2::xy(2., 2.));
+        assert_eq!(grid.at(Vec2::xy(50., 25.)), Vec2::xy(1., 0.5));
+        // Clamped outside.
+        assert_eq!(grid.at(Vec2::xy(-10., 200.)), Vec2::xy(0., 2.));
+
+        let frozen = GridField::from_field(region(), 11, 11, |p: Vec2| p);
+        assert!((frozen.at(Vec2::xy(33., 71.)) - Vec2::xy(33., 71.)).norm() < 1e-9);
+    }
+
+    /// Minimum distance between points of different lines.
+    fn closest_approach(lines: &[Vec<Vec2>]) -> f64 {
+        let mut index = SpatialIndex::new(5.);
+        for (i, line) in lines.iter().enumerate() {
+            index.extend(line.iter().map(|p| (*p, i)));
+        }
+        lines
+            .iter()
+            .enumerate()
+            .flat_map(|(i, line)| line.iter().map(move |p| (i, *p)))
+            .flat_map(|(i, p)| {
+                index
+                    .k_nearest(p, 20)
+                    .into_iter()
+                    .filter(move |(_, (_, j))| *j != i)
+                    .map(move |(_, (q, _))| (p - *q).norm())
+            })
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    #[test]
+    fn evenly_spaced_parallel_lines() {
+        let right = |_| Vec2::xy(1., 0.);
+        let lines = EvenlySpacedStreamlines::new(right, region(), 5.).generate();
+        // From the center at y = 50 to both edges, which are part of the region.
+        assert_eq!(lines.len(), 21);
+        let mut heights: Vec<f64> = lines.iter().map(|line| line[0].y).collect();
+        heights.sort_by(f64::total_cmp);
+        for pair in heights.windows(2) {
+            assert!((pair[1] - pair[0] - 5.).abs() < 1e-9);
+        }
+        // Lines span the whole region.
+        assert!(lines
+            .iter()
+            .all(|line| line[0].x < 1. && line.last().unwrap().x > 99.));
+    }
+
+    #[test]
+    fn evenly_spaced_lines_fill_the_region() {
+        let field = NoiseField::new(Perlin::seeded(1), 0.03);
+        let separation = 4.;
+        let generator = EvenlySpacedStreamlines::new(field, region(), separation).test_ratio(0.6);
+        let lines = generator.generate();
+        assert!(closest_approach(&lines) >= 0.6 * separation);
+        assert!(lines.iter().flatten().all(|p| region().contains(*p)));
+
+        // Every spot is close to some line.
+        let mut index = SpatialIndex::new(separation);
+        index.extend(lines.iter().flatten().copied());
+        for x in 0 .. 50 {
+            for y in 0 .. 50 {
+                let spot = Vec2::xy(x as f64 * 2. + 1., y as f64 * 2. + 1.);
+                let (_, nearest) = index.nearest(spot).unwrap();
+                assert!((*nearest - spot).norm() <= 1.5 * separation);
+            }
+        }
+    }
+
+    #[test]
+    fn evenly_spaced_circles_close_without_overlapping() {
+        let center = Vec2::xy(50., 50.);
+        let circular = move |p: Vec2| (p - center).perpendicular();
+        let region = Circle::new(center, 40.);
+        let lines = EvenlySpacedStreamlines::new(circular, region, 5.)
+            .seed(Vec2::xy(80., 50.))
+            .generate();
+        assert!(closest_approach(&lines) >= 2.5);
+        // Each circle ends shortly before running into its own start.
+        let first = &lines[0];
+        let gap = (first[0] - *first.last().unwrap()).norm();
+        assert!((2.5 .. 5.).contains(&gap), "{gap}");
+        assert!(lines.len() >= 7);
+    }
+
+    #[test]
+    #[should_panic(expected = "step must be positive")]
+    fn zero_step_is_rejected() {
+        // Would never get anywhere.
+        EvenlySpacedStreamlines::new(|_| Vec2::xy(1., 0.), region(), 5.).step(0.);
+    }
+
+    #[test]
+    #[should_panic(expected = "test ratio must be in (0, 1]")]
+    fn zero_test_ratio_is_rejected() {
+        // Lines would never crowd themselves, so a closed orbit would loop.
+        EvenlySpacedStreamlines::new(|_| Vec2::xy(1., 0.), region(), 5.).test_ratio(0.);
+    }
+
+    #[test]
+    fn max_steps_ends_lines() {
+        let center = Vec2::xy(50., 50.);
+        let circular = move |p: Vec2| (p - center).perpendicular();
+        let lines = EvenlySpacedStreamlines::new(circular, Circle::new(center, 40.), 5.)
+            .max_steps(10)
+            .generate();
+        assert!(!lines.is_empty());
+        assert!(lines.iter().all(|line| line.len() <= 21));
+    }
+}
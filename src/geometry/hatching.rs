@@ -0,0 +1,226 @@
+use crate::geometry::boolean::Rings;
+use crate::geometry::clipping::*;
+use crate::geometry::core::*;
+
+/// Fill a region with parallel lines, for plotters that cannot fill shapes.
+///
+/// The lines run in direction `angle`, `spacing` apart, and are clipped to the
+/// region, which may be concave and have holes. Each of the `cross_angles`
+/// adds another layer of hatching on top.
+///
+/// Hatch lines are aligned to multiples of the spacing, so adjacent shapes
+/// hatched with the same parameters line up.
+pub fn hatch<Region: Rings + ?Sized>(
+    region: &Region,
+    angle: Angle,
+    spacing: f64,
+    cross_angles: &[Angle],
+) -> Vec<Line> {
+    std::iter::once(angle)
+        .chain(cross_angles.iter().copied())
+        .flat_map(|angle| {
+            let to_world = Transformation::rotate(angle);
+            hatch_rows(region, angle, spacing)
+                .into_iter()
+                .flatten()
+                .map(move |line| line.transform(to_world))
+        })
+        .collect()
+}
+
+/// Like [`hatch`], but connect adjacent hatch lines to zig-zag paths, so the
+/// pen has to be lifted as rarely as possible.
+///
+/// Two lines are only connected if the connection stays inside the region or
+/// on its boundary.
+pub fn hatch_zigzag<Region: Rings + ?Sized>(
+    region: &Region,
+    angle: Angle,
+    spacing: f64,
+) -> Vec<Vec<Vec2>> {
+    let rows = hatch_rows(region, angle, spacing);
+
+    let rotated: Vec<Polygon> = rotated_rings(region, angle);
+    let edges: Vec<Line> = rotated.iter().flat_map(|ring| ring.edges()).collect();
+    // Same tolerance as the clipping, which connections stay within.
+    let eps = tolerance(rotated.iter().map(|ring| ring.bb()).sum());
+    let on_boundary = |p: Vec2| edges.iter().any(|edge| edge.distance_to(p) <= eps);
+    // Connections often run along the boundary, which counts as inside here.
+    let connectable = |from: Vec2, to: Vec2| {
+        clip_line(
+            Line::from_to(from, to),
+            rotated.as_slice(),
+            ClipMode::Outside,
+        )
+        .into_iter()
+        .all(|piece| on_boundary(piece.at(0.5)))
+    };
+
+    let mut used: Vec<Vec<bool>> = rows.iter().map(|row| vec![false; row.len()]).collect();
+    let mut paths = Vec::new();
+    for first_row in 0 .. rows.len() {
+        for first in 0 .. rows[first_row].len() {
+            if used[first_row][first] {
+                continue;
+            }
+            used[first_row][first] = true;
+            let segment = rows[first_row][first];
+            let mut path = vec![segment.start, segment.end];
+
+            let mut left_to_right = true;
+            for row in first_row + 1 .. rows.len() {
+                let here = *path.last().unwrap();
+                // Alternate directions: enter the next segment at the end closest to us.
+                let entry = |line: Line| if left_to_right { line.end } else { line.start };
+                let next = (0 .. rows[row].len())
+                    .filter(|&i| !used[row][i])
+                    .filter(|&i| connectable(here, entry(rows[row][i])))
+                    .min_by(|&i, &j| {
+                        let distance = |k: usize| (entry(rows[row][k]) - here).norm_square();
+                        distance(i).total_cmp(&distance(j))
+                    });
+                match next {
+                    None => break,
+                    Some(i) => {
+                        used[row][i] = true;
+                        let line = rows[row][i];
+                        let line = if left_to_right { line.reverse() } else { line };
+                        path.push(line.start);
+                        path.push(line.end);
+                        left_to_right = !left_to_right;
+                    }
+                }
+            }
+            paths.push(path);
+        }
+    }
+
+    let to_world = Transformation::rotate(angle);
+    paths
+        .into_iter()
+        .map(|path| path.iter().map(|p| p.transform(to_world)).collect())
+        .collect()
+}
+
+fn rotated_rings<Region: Rings + ?Sized>(region: &Region, angle: Angle) -> Vec<Polygon> {
+    let to_hatch_space = Transformation::rotate(Angle::rad(-angle.as_rad()));
+    region
+        .rings()
+        .into_iter()
+        .map(|ring| ring.transform(to_hatch_space))
+        .collect()
+}
+
+/// Horizontal hatch lines of the region rotated by `-angle`, grouped by row,
+/// each row sorted left to right.
+fn hatch_rows<Region: Rings + ?Sized>(
+    region: &Region,
+    angle: Angle,
+    spacing: f64,
+) -> Vec<Vec<Line>> {
+    let rotated = rotated_rings(region, angle);
+    let bb: BB = rotated.iter().map(|ring| ring.bb()).sum();
+    if rotated.is_empty() || spacing <= 0. {
+        return Vec::new();
+    }
+
+    let first = (bb.min().y / spacing - 0.5).ceil() as i64;
+    let last = (bb.max().y / spacing - 0.5).floor() as i64;
+    (first ..= last)
+        .map(|k| {
+            let y = (k as f64 + 0.5) * spacing;
+            let line = Line::from_to(Vec2::xy(bb.min().x - 1., y), Vec2::xy(bb.max().x + 1., y));
+            clip_line(line, rotated.as_slice(), ClipMode::Inside)
+        })
+        .filter(|row| !row.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::*;
+
+    fn square(size: f64) -> Polygon {
+        Polygon::from_points(vec![
+            Vec2::xy(0., 0.),
+            Vec2::xy(size, 0.),
+            Vec2::xy(size, size),
+            Vec2::xy(0., size),
+        ])
+    }
+
+    fn total_length(lines: &[Line]) -> f64 {
+        lines.iter().map(|line| line.length()).sum()
+    }
+
+    #[test]
+    fn horizontal() {
+        let lines = hatch(&square(10.), Angle::deg(0.), 1., &[]);
+        assert_eq!(lines.len(), 10);
+        assert!((total_length(&lines) - 100.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn cross_hatched() {
+        let lines = hatch(&square(10.), Angle::deg(0.), 1., &[Angle::deg(90.)]);
+        assert_eq!(lines.len(), 20);
+        for line in &lines {
+            assert!(square(10.).contains(line.at(0.5)));
+        }
+    }
+
+    #[test]
+    fn diagonal_covers_area() {
+        let lines = hatch(&square(100.), Angle::deg(30.), 1., &[]);
+        assert!((total_length(&lines) - 10_000.).abs() < 200.);
+    }
+
+    #[test]
+    fn respects_holes() {
+        let frame = square(10.).difference(&Polygon::from_points(vec![
+            Vec2::xy(2., 2.),
+            Vec2::xy(8., 2.),
+            Vec2::xy(8., 8.),
+            Vec2::xy(2., 8.),
+        ]));
+        let lines = hatch(&frame, Angle::deg(0.), 1., &[]);
+        assert!((total_length(&lines) - 64.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn zigzag_convex_is_one_path() {
+        let paths = hatch_zigzag(&square(10.), Angle::deg(0.), 1.);
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0].len(), 20);
+    }
+
+    #[test]
+    fn zigzag_far_from_the_origin() {
+        // Connections along the boundary still join at large coordinates.
+        let offset = Vec2::xy(1e7, -1e7);
+        let far = Polygon::from_points(square(10.).points().map(|p| *p + offset).collect());
+        let paths = hatch_zigzag(&far, Angle::deg(30.), 0.25);
+        assert_eq!(paths.len(), 1);
+    }
+
+    #[test]
+    fn zigzag_concave_does_not_leave_region() {
+        let u = Polygon::from_points(vec![
+            Vec2::xy(0., 0.),
+            Vec2::xy(30., 0.),
+            Vec2::xy(30., 30.),
+            Vec2::xy(20., 30.),
+            Vec2::xy(20., 10.),
+            Vec2::xy(10., 10.),
+            Vec2::xy(10., 30.),
+            Vec2::xy(0., 30.),
+        ]);
+        let paths = hatch_zigzag(&u, Angle::deg(0.), 1.);
+        assert!(paths.len() >= 2);
+        for segment in paths.iter().flat_map(|path| path.windows(2)) {
+            let midpoint = (segment[0] + segment[1]) / 2.;
+            let on_boundary = u.edges().any(|edge| edge.distance_to(midpoint) < 1e-9);
+            assert!(u.contains(midpoint) || on_boundary);
+        }
+    }
+}
@@ -0,0 +1,286 @@
+use crate::geometry::boolean::*;
+use crate::geometry::core::*;
+use crate::geometry::voronoi::*;
+
+/// Lloyd relaxation: move each point to the centroid of its Voronoi cell,
+/// which converges to a centroidal Voronoi tessellation with evenly spread
+/// points.
+///
+/// Stops after `iterations` steps, or earlier once no point moves further than
+/// `tolerance`. The region may be concave; points whose cells do not overlap
+/// it stay where they are. In concave regions, a centroid can lie outside of
+/// the region, and the point then only moves as far towards it as stays
+/// inside.
+pub fn lloyd_relaxation(
+    points: &[Vec2],
+    region: impl Into<Polygon>,
+    iterations: usize,
+    tolerance: f64,
+) -> Vec<Vec2> {
+    relax(points, region.into(), None, iterations, tolerance)
+}
+
+/// Lloyd relaxation with density-weighted centroids: points gather where the
+/// density is high. See [`lloyd_relaxation`].
+///
+/// The density must be non-negative. It is integrated numerically, so it
+/// should not vary much within a single cell.
+pub fn lloyd_relaxation_weighted(
+    points: &[Vec2],
+    region: impl Into<Polygon>,
+    density: impl Fn(Vec2) -> f64,
+    iterations: usize,
+    tolerance: f64,
+) -> Vec<Vec2> {
+    relax(points, region.into(), Some(&density), iterations, tolerance)
+}
+
+fn relax(
+    points: &[Vec2],
+    region: Polygon,
+    density: Option<&dyn Fn(Vec2) -> f64>,
+    iterations: usize,
+    tolerance: f64,
+) -> Vec<Vec2> {
+    let convex = region.is_convex();
+    // Concave regions are handled by clipping the cells of the bounding box.
+    let voronoi_region = if convex {
+        region.clone()
+    } else {
+        Polygon::from(region.bb())
+    };
+
+    let mut points = points.to_vec();
+    for _ in 0 .. iterations {
+        let cells = voronoi(&points, voronoi_region.clone());
+        let mut max_step: f64 = 0.;
+        for (point, cell) in points.iter_mut().zip(cells) {
+            let rings: Vec<Polygon> = if convex {
+                vec![cell.polygon]
+            } else {
+                cell.polygon
+                    .intersection(&region)
+                    .rings()
+                    .cloned()
+                    .collect()
+            };
+            let centroid = match density {
+                None => centroid(&rings),
+                Some(density) => weighted_centroid(&rings, density),
+            };
+            let centroid = match centroid {
+                Some(centroid) if !convex => pull_inside(&region, *point, centroid),
+                centroid => centroid,
+            };
+            if let Some(centroid) = centroid {
+                max_step = max_step.max((centroid - *point).norm());
+                *point = centroid;
+            }
+        }
+        if max_step <= tolerance {
+            break;
+        }
+    }
+    points
+}
+
+/// Centroid of an area made of rings, with holes in negative orientation.
+fn centroid(rings: &[Polygon]) -> Option<Vec2> {
+    let mut area = 0.;
+    let mut moment = Vec2::xy(0., 0.);
+    for ring in rings {
+        let ring_area = ring.area_signed();
+        area += ring_area;
+        moment += ring.centroid() * ring_area;
+    }
+    if area > 0. {
+        Some(moment / area)
+    } else {
+        None
+    }
+}
+
+/// Point on the way from `start` to `target` that lies inside the region,
+/// found by halving the way until it is. `None` if there is none, e.g.
+/// because `start` is outside too.
+fn pull_inside(region: &Polygon, start: Vec2, target: Vec2) -> Option<Vec2> {
+    let mut target = target;
+    for _ in 0 .. 32 {
+        if region.contains(target) {
+            return Some(target);
+        }
+        target = (start + target) / 2.;
+    }
+    None
+}
+
+/// Subdivisions per triangle side for the numerical integration.
+const QUADRATURE_STEPS: usize = 6;
+
+/// Density-weighted centroid of an area made of rings.
+///
+/// Each ring is decomposed into a fan of signed triangles around its first
+/// corner, and each triangle into smaller ones that sample the density at
+/// their centers. The fan stays close to the ring, so the density is rarely
+/// sampled far outside of it.
+fn weighted_centroid(rings: &[Polygon], density: &dyn Fn(Vec2) -> f64) -> Option<Vec2> {
+    let mut mass = 0.;
+    let mut moment = Vec2::xy(0., 0.);
+    let k = QUADRATURE_STEPS as f64;
+    let edges = rings.iter().flat_map(|ring| {
+        let apex = ring.points().next().copied();
+        apex.into_iter()
+            .flat_map(move |apex| ring.edges().map(move |edge| (apex, edge)))
+    });
+    for (apex, edge) in edges {
+        let u = (edge.start - apex) / k;
+        let v = (edge.end - apex) / k;
+        let sub_area = u.cross(v) / 2.;
+        for i in 0 .. QUADRATURE_STEPS {
+            for j in 0 .. QUADRATURE_STEPS - i {
+                let corner = apex + u * i as f64 + v * j as f64;
+                // Upright sub-triangle, and the inverted one next to it.
+                let mut centers = vec![corner + (u + v) / 3.];
+                if i + j + 1 < QUADRATURE_STEPS {
+                    centers.push(corner + (u + v) * (2. / 3.));
+                }
+                for center in centers {
+                    let weight = density(center) * sub_area;
+                    mass += weight;
+                    moment += center * weight;
+                }
+            }
+        }
+    }
+    if mass > 0. {
+        Some(moment / mass)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::*;
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+    fn random_points(n: usize, seed: u64) -> Vec<Vec2> {
+        let mut rng = SmallRng::seed_from_u64(seed);
+        (0 .. n)
+            .map(|_| Vec2::xy(rng.gen_range(0. .. 100.), rng.gen_range(0. .. 100.)))
+            .collect()
+    }
+
+    fn region() -> BB {
+        (Vec2::xy(0., 0.), Vec2::xy(100., 100.)).bb()
+    }
+
+    /// Ratio of largest to smallest Voronoi cell.
+    fn spread(points: &[Vec2], region: impl Into<Polygon>) -> f64 {
+        let areas: Vec<f64> = voronoi(points, region)
+            .iter()
+            .map(|cell| cell.polygon.area())
+            .collect();
+        let max = areas.iter().cloned().fold(0., f64::max);
+        let min = areas.iter().cloned().fold(f64::INFINITY, f64::min);
+        max / min
+    }
+
+    #[test]
+    fn relaxation_evens_out_cells() {
+        let points = random_points(100, 0);
+        let relaxed = lloyd_relaxation(&points, region(), 50, 0.);
+        assert_eq!(relaxed.len(), points.len());
+        assert!(spread(&relaxed, region()) < spread(&points, region()) / 5.);
+        assert!(spread(&relaxed, region()) < 2.);
+    }
+
+    #[test]
+    fn stops_at_tolerance() {
+        let grid: Vec<Vec2> = (0 .. 16)
+            .map(|i| Vec2::xy((i % 4) as f64 * 25. + 12.5, (i / 4) as f64 * 25. + 12.5))
+            .collect();
+        // Already centroidal, so nothing moves.
+        let relaxed = lloyd_relaxation(&grid, region(), 10, 1e-9);
+        for (a, b) in relaxed.iter().zip(&grid) {
+            assert!((*a - *b).norm() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn concave_region() {
+        let l_shape = Polygon::from_points(vec![
+            Vec2::xy(0., 0.),
+            Vec2::xy(100., 0.),
+            Vec2::xy(100., 50.),
+            Vec2::xy(50., 50.),
+            Vec2::xy(50., 100.),
+            Vec2::xy(0., 100.),
+        ]);
+        let points: Vec<Vec2> = random_points(200, 1)
+            .into_iter()
+            .filter(|p| l_shape.contains(*p))
+            .collect();
+        let relaxed = lloyd_relaxation(&points, l_shape.clone(), 20, 0.);
+        assert!(relaxed.iter().all(|p| l_shape.contains(*p)));
+    }
+
+    #[test]
+    fn points_stay_inside_thin_concave_region() {
+        // The centroid of a thin U is outside of it, and so are the centroids
+        // of the L-shaped halves.
+        let u_shape = Polygon::from_points(vec![
+            Vec2::xy(0., 0.),
+            Vec2::xy(100., 0.),
+            Vec2::xy(100., 100.),
+            Vec2::xy(95., 100.),
+            Vec2::xy(95., 5.),
+            Vec2::xy(5., 5.),
+            Vec2::xy(5., 100.),
+            Vec2::xy(0., 100.),
+        ]);
+        assert!(!u_shape.contains(u_shape.centroid()));
+        for points in [
+            vec![Vec2::xy(2., 50.)],
+            vec![Vec2::xy(2., 50.), Vec2::xy(98., 50.)],
+        ] {
+            let relaxed = lloyd_relaxation(&points, u_shape.clone(), 20, 0.);
+            assert!(relaxed.iter().all(|p| u_shape.contains(*p)), "{relaxed:?}");
+            let weighted =
+                lloyd_relaxation_weighted(&points, u_shape.clone(), |p| 1. + p.y, 20, 0.);
+            assert!(
+                weighted.iter().all(|p| u_shape.contains(*p)),
+                "{weighted:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn density_attracts_points() {
+        let points = random_points(200, 2);
+        let density = |p: Vec2| if p.x < 50. { 9. } else { 1. };
+        let relaxed = lloyd_relaxation_weighted(&points, region(), density, 100, 0.);
+        let left = relaxed.iter().filter(|p| p.x < 50.).count();
+        // The limit would be 150 points, but Lloyd converges slowly.
+        assert!(left > 110, "only {left} points in the dense half");
+    }
+
+    #[test]
+    fn weighted_centroid_of_linear_density() {
+        let square = Polygon::from(region());
+        let centroid = super::weighted_centroid(&[square], &|p| p.x).unwrap();
+        // ∫x² / ∫x over [0, 100] is 200/3, up to the quadrature error.
+        assert!((centroid.x - 200. / 3.).abs() < 0.5, "{centroid:?}");
+        assert!((centroid.y - 50.).abs() < 0.5, "{centroid:?}");
+    }
+
+    #[test]
+    fn constant_density_matches_unweighted() {
+        let points = random_points(50, 3);
+        let plain = lloyd_relaxation(&points, region(), 3, 0.);
+        let weighted = lloyd_relaxation_weighted(&points, region(), |_| 1., 3, 0.);
+        for (a, b) in plain.iter().zip(&weighted) {
+            assert!((*a - *b).norm() < 1e-6);
+        }
+    }
+}
@@ -1,5 +1,33 @@
+mod boolean;
+mod clipping;
 mod core;
+mod delaunay;
+mod ear_clipping;
+mod flow_field;
+mod hatching;
+mod lloyd;
+mod offset;
 mod poisson_disc;
+mod quadtree;
+mod smoothing;
+mod spatial_index;
+mod spline;
+mod stippling;
+mod voronoi;
 
 pub use self::core::*;
+pub use boolean::*;
+pub use clipping::*;
+pub use delaunay::*;
+pub use ear_clipping::*;
+pub use flow_field::*;
+pub use hatching::*;
+pub use lloyd::*;
+pub use offset::*;
 pub use poisson_disc::*;
+pub use quadtree::*;
+pub use smoothing::*;
+pub use spatial_index::*;
+pub use spline::*;
+pub use stippling::*;
+pub use voronoi::*;
@@ -0,0 +1,231 @@
+use crate::geometry::boolean::*;
+use crate::geometry::core::*;
+
+/// How to connect offset edges at corners where they move apart.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Join {
+    /// Extend the edges until they meet, unless the tip would be further than
+    /// `limit` times the offset distance from the corner, in which case the
+    /// corner is beveled.
+    Miter { limit: f64 },
+    /// Circular arc around the corner.
+    Round,
+    /// Straight line between the offset edges.
+    Bevel,
+}
+
+/// Grow (positive distance) or shrink (negative distance) a region.
+///
+/// Works for concave regions with holes. Parts that vanish or overlap due to
+/// large offsets are removed, so the result always consists of simple rings.
+///
+/// Offset rings are constructed edge by edge, then cleaned up with the
+/// [`FillRule::Positive`] rule: the inverted loops a too-large offset produces
+/// wind in negative direction and disappear.
+pub fn offset<Region: Rings + ?Sized>(region: &Region, distance: f64, join: Join) -> MultiPolygon {
+    // Normalize first, so outer rings are positive and holes negative. Then
+    // the outside is always to the right of an edge.
+    let normalized = resolve(region, FillRule::EvenOdd);
+    if distance == 0. {
+        return normalized;
+    }
+    let raw: Vec<Polygon> = normalized
+        .rings()
+        .map(|ring| offset_ring(ring, distance, join))
+        .collect();
+    resolve(raw.as_slice(), FillRule::Positive)
+}
+
+/// Concentric contours for plotter “contour fill”: the region shrunk by
+/// `spacing`, `2 * spacing`, … until nothing is left.
+pub fn inset_contours<Region: Rings + ?Sized>(
+    region: &Region,
+    spacing: f64,
+    join: Join,
+) -> Vec<MultiPolygon> {
+    let normalized = resolve(region, FillRule::EvenOdd);
+    let spacing = spacing.abs();
+    if spacing == 0. {
+        return Vec::new();
+    }
+    (1 ..)
+        .map(|i| offset(&normalized, -spacing * i as f64, join))
+        .take_while(|contour| !contour.is_empty())
+        .collect()
+}
+
+fn offset_ring(ring: &Polygon, distance: f64, join: Join) -> Polygon {
+    let points: Vec<Vec2> = ring.points().copied().collect();
+    let n = points.len();
+    let mut result = Vec::with_capacity(2 * n);
+    for i in 0 .. n {
+        let prev = points[(i + n - 1) % n];
+        let here = points[i];
+        let next = points[(i + 1) % n];
+        let (incoming, outgoing) = (here - prev, next - here);
+        if incoming.norm() == 0. || outgoing.norm() == 0. {
+            continue;
+        }
+        let n1 = outward_normal(incoming);
+        let n2 = outward_normal(outgoing);
+
+        let diverging = incoming.cross(outgoing) * distance > 0.;
+        if !diverging {
+            // The offset edges overlap. Going through the original corner makes
+            // the overlap a small negative loop that the cleanup removes.
+            result.push(here + n1 * distance);
+            result.push(here);
+            result.push(here + n2 * distance);
+            continue;
+        }
+
+        match join {
+            Join::Bevel => {
+                result.push(here + n1 * distance);
+                result.push(here + n2 * distance);
+            }
+            Join::Miter { limit } => {
+                // Distance from the corner to the miter tip is |d| / cos(θ/2),
+                // with θ the angle between the normals.
+                let cos_half = ((1. + n1.dot(n2)) / 2.).sqrt();
+                if cos_half > 0. && 1. / cos_half <= limit {
+                    result.push(here + (n1 + n2) * (distance / (1. + n1.dot(n2))));
+                } else {
+                    result.push(here + n1 * distance);
+                    result.push(here + n2 * distance);
+                }
+            }
+            Join::Round => {
+                let radius = distance.abs();
+                let angle = n1.cross(n2).atan2(n1.dot(n2));
+                // Keep the arc within a thousandth of the radius.
+                let max_step = 2. * (1. - 1e-3_f64).acos();
+                let steps = (angle.abs() / max_step).ceil().max(1.) as usize;
+                let start = (n1 * distance.signum()).angle().as_rad();
+                for step in 0 ..= steps {
+                    let phi = start + angle * step as f64 / steps as f64;
+                    result.push(here + Vec2::polar(radius, Angle::rad(phi)));
+                }
+            }
+        }
+    }
+    Polygon::from_points(result)
+}
+
+/// Unit normal to the right of the direction, which is outside for positively
+/// oriented rings.
+fn outward_normal(direction: Vec2) -> Vec2 {
+    -direction.perpendicular() / direction.norm()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::*;
+    use std::f64::consts::PI;
+
+    fn square(size: f64) -> Polygon {
+        Polygon::from_points(vec![
+            Vec2::xy(0., 0.),
+            Vec2::xy(size, 0.),
+            Vec2::xy(size, size),
+            Vec2::xy(0., size),
+        ])
+    }
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() < tolerance,
+            "{actual} != {expected}"
+        );
+    }
+
+    #[test]
+    fn outset_joins() {
+        let miter = offset(&square(10.), 1., Join::Miter { limit: 2. });
+        assert_close(miter.area(), 144., 1e-9);
+        assert_eq!(miter.parts().next().unwrap().outer.points().count(), 4);
+
+        let bevel = offset(&square(10.), 1., Join::Bevel);
+        assert_close(bevel.area(), 142., 1e-9);
+
+        let round = offset(&square(10.), 1., Join::Round);
+        assert_close(round.area(), 140. + PI, 1e-2);
+    }
+
+    #[test]
+    fn miter_limit_bevels_sharp_corners() {
+        let spike = Polygon::from_points(vec![
+            Vec2::xy(0., 0.),
+            Vec2::xy(100., 5.),
+            Vec2::xy(0., 10.),
+        ]);
+        let limited = offset(&spike, 1., Join::Miter { limit: 2. });
+        assert!(limited.bb().max().x < 102.);
+        let unlimited = offset(&spike, 1., Join::Miter { limit: 100. });
+        assert!(unlimited.bb().max().x > 110.);
+    }
+
+    #[test]
+    fn inset_and_orientation() {
+        let mut points: Vec<Vec2> = square(10.).points().copied().collect();
+        points.reverse();
+        let clockwise = Polygon::from_points(points);
+        let inset = offset(&clockwise, -1., Join::Miter { limit: 2. });
+        assert_close(inset.area(), 64., 1e-9);
+        assert_eq!(
+            inset.parts().next().unwrap().outer.orientation(),
+            Orientation::Positive
+        );
+    }
+
+    #[test]
+    fn inset_vanishes() {
+        assert!(offset(&square(10.), -6., Join::Round).is_empty());
+    }
+
+    #[test]
+    fn inset_concave_splits() {
+        // Dumbbell: two squares joined by a thin bridge.
+        let dumbbell = Polygon::from_points(vec![
+            Vec2::xy(0., 0.),
+            Vec2::xy(10., 0.),
+            Vec2::xy(10., 4.),
+            Vec2::xy(20., 4.),
+            Vec2::xy(20., 0.),
+            Vec2::xy(30., 0.),
+            Vec2::xy(30., 10.),
+            Vec2::xy(20., 10.),
+            Vec2::xy(20., 6.),
+            Vec2::xy(10., 6.),
+            Vec2::xy(10., 10.),
+            Vec2::xy(0., 10.),
+        ]);
+        let inset = offset(&dumbbell, -2., Join::Miter { limit: 2. });
+        assert_eq!(inset.len(), 2);
+        assert_close(inset.area(), 2. * 36., 1e-9);
+    }
+
+    #[test]
+    fn outset_closes_holes() {
+        let frame = square(10.).difference(&Polygon::from_points(vec![
+            Vec2::xy(4., 4.),
+            Vec2::xy(6., 4.),
+            Vec2::xy(6., 6.),
+            Vec2::xy(4., 6.),
+        ]));
+        let small = offset(&frame, 0.5, Join::Miter { limit: 2. });
+        assert_eq!(small.parts().next().unwrap().holes.len(), 1);
+        assert_close(small.area(), 121. - 1., 1e-9);
+
+        let large = offset(&frame, 1.5, Join::Miter { limit: 2. });
+        assert!(large.parts().next().unwrap().holes.is_empty());
+        assert_close(large.area(), 169., 1e-9);
+    }
+
+    #[test]
+    fn contour_fill() {
+        let contours = inset_contours(&square(10.), 1., Join::Miter { limit: 2. });
+        assert_eq!(contours.len(), 4);
+        assert_close(contours[3].area(), 4., 1e-9);
+    }
+}
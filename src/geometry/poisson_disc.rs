@@ -1,47 +1,54 @@
 use crate::geometry::core::*;
 use rand::Rng;
-use std::ops::{Index, IndexMut};
+use std::f64::consts::TAU;
 use std::vec;
 
 /// Sample a number of points to yield a visually uniform point distribution.
-pub fn poisson_disc<R: Rng, Region: HasBB>(
+///
+/// Only points inside the region are emitted. The sampling starts at the
+/// center of the region’s bounding box, or at a random point of the region if
+/// the center is outside; regions too small to hit by chance yield no points.
+/// New points are placed just beyond `radius` from existing ones, so parts of
+/// the region separated from the rest by a gap wider than `radius` are only
+/// reached if they contain the starting point.
+///
+/// Panics unless the radius is positive and finite.
+pub fn poisson_disc<R: Rng, Region: HasBB + Contains>(
     rng: &mut R,
     region: Region,
     radius: f64,
     k: usize,
 ) -> Vec<Vec2> {
     let bb = region.bb();
-    let initial_point = region.bb().center();
-    let mut grid = Grid::new(radius / 2_f64.sqrt(), bb);
-    let mut active_points = Vec::with_capacity((grid.size() as f64).sqrt() as usize);
-    // ^ Not sure about a good initial size, but sqrt(grid size) should be a good starting point.
+    let initial_point = match initial_point(rng, bb, |p| region.contains(p)) {
+        Some(point) => point,
+        None => return Vec::new(),
+    };
+    let mut grid = Grid::new(radius, bb);
+    let mut active_points = vec![initial_point];
     grid.insert(initial_point);
-    active_points.push(initial_point);
-    let mut result = Vec::with_capacity(grid.size());
+    // A dense packing has about one point per r², i.e. per two grid cells.
+    let mut result = Vec::with_capacity(grid.size() / 2);
+    result.push(initial_point);
 
     while let Some(active_i) = random_index(rng, &active_points) {
         let active_sample = active_points[active_i];
 
-        let new_point: Option<Vec2> =
-            quality_candidates_around_sample(rng, k, radius, active_sample)
-                .filter(|candidate| candidate.bb().is_inside(bb))
-                .find(|candidate| {
-                    let neighbours = grid.neighbouring_points(*candidate);
-                    let too_close =
-                        |neighbour| (*candidate - neighbour).norm_square() <= radius.powi(2);
-                    !neighbours.into_iter().any(too_close)
-                });
+        // Cheapest checks first: the region might be an expensive polygon.
+        let new_point = candidates_on_annulus(rng, k, radius, active_sample)
+            .filter(|candidate| bb.contains(*candidate))
+            .filter(|candidate| grid.is_free(*candidate))
+            .find(|candidate| region.contains(*candidate));
 
         match new_point {
             None => {
                 // No new point found. Retire sample.
                 active_points.swap_remove(active_i);
-                result.push(active_sample);
             }
             Some(new) => {
-                // New point found. Mark it as active, and insert its location into the grid for future collision detection.
                 grid.insert(new);
                 active_points.push(new);
+                result.push(new);
             }
         }
     }
@@ -49,7 +56,411 @@ pub fn poisson_disc<R: Rng, Region: HasBB>(
     result
 }
 
-fn random_index<R: Rng, T>(rng: &mut R, vec: &Vec<T>) -> Option<usize> {
+/// Poisson disc sampling with a minimum distance that varies across the
+/// region.
+///
+/// Two points `p` and `q` are at least `(radius(p) + radius(q)) / 2` apart, so
+/// a constant radius gives the same spacing as [`poisson_disc`]. Candidates
+/// where the radius is not positive are rejected. Radii far below a
+/// thousandth of the region’s size work, but slowly, because the lookup grid
+/// is limited to about 1000×1000 cells.
+pub fn poisson_disc_variable<R: Rng, Region: HasBB + Contains>(
+    rng: &mut R,
+    region: Region,
+    radius: impl Fn(Vec2) -> f64,
+    k: usize,
+) -> Vec<Vec2> {
+    let bb = region.bb();
+    let valid_radius = |r: f64| r > 0. && r.is_finite();
+    let Some(initial_point) = initial_point(rng, bb, |p| region.contains(p)) else {
+        return Vec::new();
+    };
+    let initial_radius = radius(initial_point);
+    if !valid_radius(initial_radius) {
+        return Vec::new();
+    }
+
+    // The grid cell size only affects speed, not correctness, so a coarse
+    // estimate of the smallest radius is good enough. Radii close to 0 would
+    // make the grid huge, so the cells have a minimum size.
+    let (min, max) = (bb.min(), bb.max());
+    let steps = 32;
+    let smallest_radius = (0 ..= steps)
+        .flat_map(|i| (0 ..= steps).map(move |j| (i, j)))
+        .map(|(i, j)| {
+            let t = Vec2::xy(i as f64, j as f64) / steps as f64;
+            radius(Vec2::xy(
+                min.x + t.x * (max.x - min.x),
+                min.y + t.y * (max.y - min.y),
+            ))
+        })
+        .filter(|r| valid_radius(*r))
+        .fold(initial_radius, f64::min);
+    let coarsest = (max.x - min.x).max(max.y - min.y) / MAX_CELLS_PER_SIDE as f64;
+    let mut grid = BucketGrid::new((smallest_radius / 2_f64.sqrt()).max(coarsest), bb);
+
+    let mut active_points = vec![(initial_point, initial_radius)];
+    grid.insert(initial_point, initial_radius);
+    let mut result = Vec::new();
+
+    while let Some(active_i) = random_index(rng, &active_points) {
+        let (active_sample, active_radius) = active_points[active_i];
+
+        let new_point = quality_candidates_around_sample(rng, k, active_radius, active_sample)
+            .filter(|candidate| region.contains(*candidate))
+            .map(|candidate| (candidate, radius(candidate)))
+            .filter(|(_, r)| valid_radius(*r))
+            .find(|&(candidate, r)| {
+                // No point can be further away and still conflict.
+                let reach = (r + grid.largest_radius) / 2.;
+                !grid
+                    .points_within(candidate, reach)
+                    .any(|(neighbour, neighbour_r)| {
+                        (candidate - neighbour).norm() < (r + neighbour_r) / 2.
+                    })
+            });
+
+        match new_point {
+            None => {
+                active_points.swap_remove(active_i);
+                result.push(active_sample);
+            }
+            Some((new, r)) => {
+                grid.insert(new, r);
+                active_points.push((new, r));
+            }
+        }
+    }
+    result
+}
+
+/// Limit for the variable-radius grid along each axis.
+const MAX_CELLS_PER_SIDE: usize = 1000;
+
+/// Configurable Poisson disc sampling that grows around existing points,
+/// avoids obstacles, and yields points one by one as they are placed.
+pub struct PoissonDisc<Region> {
+    region: Region,
+    radius: f64,
+    k: usize,
+    initial_points: Vec<Vec2>,
+    obstacles: Vec<Box<dyn Contains>>,
+}
+
+impl<Region: HasBB + Contains> PoissonDisc<Region> {
+    /// Panics unless the radius is positive and finite.
+    pub fn new(region: Region, radius: f64) -> Self {
+        assert!(
+            radius > 0. && radius.is_finite(),
+            "radius must be positive and finite"
+        );
+        PoissonDisc {
+            region,
+            radius,
+            k: 20,
+            initial_points: Vec::new(),
+            obstacles: Vec::new(),
+        }
+    }
+
+    /// Number of candidates tried around a point before it is retired.
+    pub fn k(mut self, k: usize) -> Self {
+        self.k = k;
+        self
+    }
+
+    /// Points that are already placed. They stay where they are and are not
+    /// part of the output, but new points keep their distance, and the
+    /// sampling grows outwards from them.
+    pub fn initial_points(mut self, points: impl IntoIterator<Item = Vec2>) -> Self {
+        self.initial_points.extend(points);
+        self
+    }
+
+    /// Area to keep free of points.
+    pub fn obstacle(mut self, obstacle: impl Contains + 'static) -> Self {
+        self.obstacles.push(Box::new(obstacle));
+        self
+    }
+
+    /// Start sampling. Nothing is computed until the iterator is advanced.
+    pub fn sample<R: Rng>(self, rng: &mut R) -> PoissonDiscSamples<'_, R, Region> {
+        let mut grid = BucketGrid::new(self.radius / 2_f64.sqrt(), self.region.bb());
+        for &point in &self.initial_points {
+            grid.insert(point, self.radius);
+        }
+        PoissonDiscSamples {
+            rng,
+            active_points: self.initial_points.clone(),
+            grid,
+            pending_start: self.initial_points.is_empty(),
+            config: self,
+        }
+    }
+
+    fn allows(&self, candidate: Vec2) -> bool {
+        self.region.contains(candidate)
+            && !self
+                .obstacles
+                .iter()
+                .any(|obstacle| obstacle.contains(candidate))
+    }
+}
+
+/// Iterator over the points of a [`PoissonDisc`] sampling, in the order they
+/// are placed.
+pub struct PoissonDiscSamples<'a, R, Region> {
+    rng: &'a mut R,
+    config: PoissonDisc<Region>,
+    grid: BucketGrid,
+    active_points: Vec<Vec2>,
+    /// Without initial points, the first point still has to be chosen.
+    pending_start: bool,
+}
+
+impl<R: Rng, Region: HasBB + Contains> Iterator for PoissonDiscSamples<'_, R, Region> {
+    type Item = Vec2;
+
+    fn next(&mut self) -> Option<Vec2> {
+        let radius = self.config.radius;
+        if self.pending_start {
+            self.pending_start = false;
+            let config = &self.config;
+            let start = initial_point(self.rng, config.region.bb(), |p| config.allows(p))?;
+            self.grid.insert(start, radius);
+            self.active_points.push(start);
+            return Some(start);
+        }
+
+        while let Some(active_i) = random_index(self.rng, &self.active_points) {
+            let active_sample = self.active_points[active_i];
+            let (config, grid) = (&self.config, &self.grid);
+            let new_point = candidates_on_annulus(self.rng, config.k, radius, active_sample)
+                .filter(|candidate| config.allows(*candidate))
+                .find(|candidate| {
+                    !grid
+                        .points_within(*candidate, radius)
+                        .any(|(neighbour, _)| {
+                            (*candidate - neighbour).norm_square() <= radius.powi(2)
+                        })
+                });
+            match new_point {
+                None => {
+                    self.active_points.swap_remove(active_i);
+                }
+                Some(new) => {
+                    self.grid.insert(new, radius);
+                    self.active_points.push(new);
+                    return Some(new);
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Poisson disc sampling on a torus: the point set continues seamlessly when
+/// the region is repeated in both directions, which makes it suitable for
+/// tiling textures.
+///
+/// All points lie in the region; the minimum distance also holds across
+/// opposite edges. Panics unless the radius is positive and finite.
+pub fn poisson_disc_periodic<R: Rng>(rng: &mut R, region: BB, radius: f64, k: usize) -> Vec<Vec2> {
+    let (min, period) = (region.min(), region.max() - region.min());
+    let wrap = |p: Vec2| {
+        min + Vec2::xy(
+            (p.x - min.x).rem_euclid(period.x),
+            (p.y - min.y).rem_euclid(period.y),
+        )
+    };
+    let mut grid = BucketGrid::periodic(radius / 2_f64.sqrt(), region);
+
+    let start = region.center();
+    grid.insert(start, radius);
+    let mut active_points = vec![start];
+    let mut result = Vec::new();
+    while let Some(active_i) = random_index(rng, &active_points) {
+        let active_sample = active_points[active_i];
+        let new_point = candidates_on_annulus(rng, k, radius, active_sample)
+            .map(wrap)
+            .find(|candidate| {
+                !grid
+                    .points_within(*candidate, radius)
+                    .any(|(neighbour, _)| {
+                        grid.difference(*candidate, neighbour).norm_square() <= radius.powi(2)
+                    })
+            });
+        match new_point {
+            None => {
+                active_points.swap_remove(active_i);
+                result.push(active_sample);
+            }
+            Some(new) => {
+                grid.insert(new, radius);
+                active_points.push(new);
+            }
+        }
+    }
+    result
+}
+
+/// Uniform grid over a bounding box that stores any number of points per cell,
+/// together with their radius. Periodic grids wrap around at the edges.
+struct BucketGrid {
+    origin: Vec2,
+    cell_size: Vec2,
+    size_x: usize,
+    size_y: usize,
+    cells: Vec<Vec<(Vec2, f64)>>,
+    largest_radius: f64,
+    /// Size of the torus, for periodic grids.
+    period: Option<Vec2>,
+}
+
+impl BucketGrid {
+    /// Grid with cells of the given size. Points outside the bounding box are
+    /// put in the closest cell.
+    fn new(cell_size: f64, bb: BB) -> Self {
+        assert!(
+            cell_size > 0. && cell_size.is_finite(),
+            "cell size must be positive and finite"
+        );
+        let extent = bb.max() - bb.min();
+        let size_x = (extent.x / cell_size).floor() as usize + 1;
+        let size_y = (extent.y / cell_size).floor() as usize + 1;
+        Self::with_cells(
+            bb.min(),
+            Vec2::xy(cell_size, cell_size),
+            size_x,
+            size_y,
+            None,
+        )
+    }
+
+    /// Grid on the torus that the bounding box becomes by gluing opposite
+    /// edges. Cells are at least of the given size.
+    fn periodic(cell_size: f64, bb: BB) -> Self {
+        assert!(
+            cell_size > 0. && cell_size.is_finite(),
+            "cell size must be positive and finite"
+        );
+        let extent = bb.max() - bb.min();
+        let size_x = ((extent.x / cell_size).floor() as usize).max(1);
+        let size_y = ((extent.y / cell_size).floor() as usize).max(1);
+        let cell_size = Vec2::xy(extent.x / size_x as f64, extent.y / size_y as f64);
+        Self::with_cells(bb.min(), cell_size, size_x, size_y, Some(extent))
+    }
+
+    fn with_cells(
+        origin: Vec2,
+        cell_size: Vec2,
+        size_x: usize,
+        size_y: usize,
+        period: Option<Vec2>,
+    ) -> Self {
+        BucketGrid {
+            origin,
+            cell_size,
+            size_x,
+            size_y,
+            cells: vec![Vec::new(); size_x * size_y],
+            largest_radius: 0.,
+            period,
+        }
+    }
+
+    /// Cell coordinates, possibly outside of the grid.
+    fn cell(&self, point: Vec2) -> (isize, isize) {
+        let relative = point - self.origin;
+        (
+            (relative.x / self.cell_size.x).floor() as isize,
+            (relative.y / self.cell_size.y).floor() as isize,
+        )
+    }
+
+    /// Move cell coordinates into the grid, by wrapping or clamping.
+    fn normalize(&self, i: isize, size: usize) -> usize {
+        if self.period.is_some() {
+            i.rem_euclid(size as isize) as usize
+        } else {
+            i.clamp(0, size as isize - 1) as usize
+        }
+    }
+
+    fn insert(&mut self, point: Vec2, radius: f64) {
+        let (ix, iy) = self.cell(point);
+        let (ix, iy) = (
+            self.normalize(ix, self.size_x),
+            self.normalize(iy, self.size_y),
+        );
+        self.cells[iy * self.size_x + ix].push((point, radius));
+        self.largest_radius = self.largest_radius.max(radius);
+    }
+
+    /// Shortest vector from b to a, which may cross the edges of a periodic
+    /// grid.
+    fn difference(&self, a: Vec2, b: Vec2) -> Vec2 {
+        let d = a - b;
+        match self.period {
+            None => d,
+            Some(period) => Vec2::xy(
+                d.x - period.x * (d.x / period.x).round(),
+                d.y - period.y * (d.y / period.y).round(),
+            ),
+        }
+    }
+
+    /// All points in cells that overlap the square around the center, each
+    /// at most once.
+    fn points_within(&self, center: Vec2, distance: f64) -> impl Iterator<Item = (Vec2, f64)> + '_ {
+        let offset = Vec2::xy(distance, distance);
+        let (min_x, min_y) = self.cell(center - offset);
+        let (max_x, max_y) = self.cell(center + offset);
+        let xs = self.cell_indices(min_x, max_x, self.size_x);
+        let ys = self.cell_indices(min_y, max_y, self.size_y);
+        ys.flat_map(move |iy| xs.clone().map(move |ix| iy * self.size_x + ix))
+            .flat_map(move |i| self.cells[i].iter().copied())
+    }
+
+    fn cell_indices(
+        &self,
+        min: isize,
+        max: isize,
+        size: usize,
+    ) -> impl Iterator<Item = usize> + Clone + '_ {
+        let (min, max) = if self.period.is_some() && max - min + 1 >= size as isize {
+            // The range covers the whole torus; avoid visiting cells twice.
+            (0, size as isize - 1)
+        } else if self.period.is_some() {
+            (min, max)
+        } else {
+            (
+                min.clamp(0, size as isize - 1),
+                max.clamp(0, size as isize - 1),
+            )
+        };
+        (min ..= max).map(move |i| self.normalize(i, size))
+    }
+}
+
+/// Start at the center of the bounding box if possible, or else at a random
+/// point inside.
+fn initial_point<R: Rng>(rng: &mut R, bb: BB, inside: impl Fn(Vec2) -> bool) -> Option<Vec2> {
+    if inside(bb.center()) {
+        return Some(bb.center());
+    }
+    let (min, max) = (bb.min(), bb.max());
+    (0 .. 10_000)
+        .map(|_| {
+            Vec2::xy(
+                rng.gen_range(min.x ..= max.x),
+                rng.gen_range(min.y ..= max.y),
+            )
+        })
+        .find(|candidate| inside(*candidate))
+}
+
+fn random_index<R: Rng, T>(rng: &mut R, vec: &[T]) -> Option<usize> {
     if vec.is_empty() {
         None
     } else {
@@ -59,7 +470,9 @@ fn random_index<R: Rng, T>(rng: &mut R, vec: &Vec<T>) -> Option<usize> {
 
 /// Sample new points in an annulus of (r,2r) around the center.
 ///
-/// Yields good results with medium-sized k (e.g. 20).
+/// Yields good results with medium-sized k (e.g. 20). Used where radii vary,
+/// because a candidate right at the active point’s radius would conflict with
+/// it whenever the radius grows towards the candidate.
 fn quality_candidates_around_sample<R: Rng>(
     rng: &mut R,
     k: usize,
@@ -74,91 +487,324 @@ fn quality_candidates_around_sample<R: Rng>(
     })
 }
 
+/// Candidates just outside the inner circle of the annulus around the center,
+/// evenly spaced from a random starting angle.
+///
+/// Packs points more densely and rejects fewer candidates than
+/// [`quality_candidates_around_sample`] (Roberts 2019, improving on Bridson
+/// 2007).
+fn candidates_on_annulus<R: Rng>(
+    rng: &mut R,
+    k: usize,
+    radius: f64,
+    center: Vec2,
+) -> impl Iterator<Item = Vec2> {
+    // Slightly larger than the radius, so the candidate is not rejected by the
+    // sample it was generated from.
+    let r = radius * (1. + 1e-7);
+    let start = Vec2::polar(r, Angle::rad(rng.gen_range(0. .. TAU)));
+    let step = Vec2::polar(1., Angle::rad(TAU / k as f64));
+    // Rotating by complex multiplication avoids trigonometry in the loop.
+    (0 .. k).scan(start, move |offset, _| {
+        let candidate = center + *offset;
+        *offset = Vec2::xy(
+            offset.x * step.x - offset.y * step.y,
+            offset.x * step.y + offset.y * step.x,
+        );
+        Some(candidate)
+    })
+}
+
+/// Grid over a bounding box with cells of size `radius / √2`, so that each
+/// cell holds at most one point.
+///
+/// The cells are stored row by row with a margin of two empty cells around the
+/// bounding box, so looking up the neighbourhood of a point needs no bounds
+/// checks. Empty cells hold a point at infinity, which is never too close
+/// to anything.
 struct Grid {
-    cell_size: f64,
+    origin: Vec2,
+    radius_square: f64,
+    inverse_cell_size: f64,
     size_x: usize,
     size_y: usize,
-    vec: Vec<Option<Vec2>>,
+    cells: Vec<Vec2>,
+    /// Index offsets of the cells that can hold points within the radius,
+    /// nearest first to reject candidates early.
+    neighbourhood: Vec<isize>,
 }
 
+const MARGIN: usize = 2;
+
 impl Grid {
-    pub fn new(cell_size: f64, bb: BB) -> Self {
-        let size_x = (bb.max().x / cell_size).ceil() as usize;
-        let size_y = (bb.max().y / cell_size).ceil() as usize;
-        let size = size_x * size_y;
+    fn new(radius: f64, bb: BB) -> Self {
+        assert!(
+            radius > 0. && radius.is_finite(),
+            "radius must be positive and finite"
+        );
+        let cell_size = radius / 2_f64.sqrt();
+        // One extra cell, so that points on the upper edges still fit.
+        let extent = bb.max() - bb.min();
+        let size_x = (extent.x / cell_size).floor() as usize + 1;
+        let size_y = (extent.y / cell_size).floor() as usize + 1;
+        let empty = Vec2::xy(f64::INFINITY, f64::INFINITY);
+        let stride = (size_x + 2 * MARGIN) as isize;
+        // The 5×5 cells around a point, without the corners, which are more
+        // than the radius away from the center cell.
+        let mut neighbourhood: Vec<(isize, isize)> = (-2_isize ..= 2)
+            .flat_map(|dy| (-2 ..= 2).map(move |dx| (dx, dy)))
+            .filter(|(dx, dy)| (dx * dy).abs() != 4)
+            .collect();
+        neighbourhood.sort_by_key(|(dx, dy)| dx * dx + dy * dy);
         Grid {
-            cell_size,
+            origin: bb.min(),
+            radius_square: radius * radius,
+            inverse_cell_size: cell_size.recip(),
             size_x,
             size_y,
-            vec: vec![None; size],
+            cells: vec![empty; (size_x + 2 * MARGIN) * (size_y + 2 * MARGIN)],
+            neighbourhood: neighbourhood
+                .into_iter()
+                .map(|(dx, dy)| dy * stride + dx)
+                .collect(),
         }
     }
 
-    pub fn size(&self) -> usize {
+    /// Number of cells inside the bounding box.
+    fn size(&self) -> usize {
         self.size_x * self.size_y
     }
 
-    fn cell(&self, point: Vec2) -> (usize, usize) {
-        (
-            (point.x / self.cell_size).floor() as usize,
-            (point.y / self.cell_size).floor() as usize,
-        )
+    fn stride(&self) -> usize {
+        self.size_x + 2 * MARGIN
+    }
+
+    /// Index of the cell of a point inside the bounding box.
+    fn index(&self, point: Vec2) -> usize {
+        let relative = (point - self.origin) * self.inverse_cell_size;
+        let ix = (relative.x as usize).min(self.size_x - 1) + MARGIN;
+        let iy = (relative.y as usize).min(self.size_y - 1) + MARGIN;
+        iy * self.stride() + ix
     }
 
-    pub fn insert(&mut self, point: Vec2) {
-        let index = self.cell(point);
-        self[index] = Some(point);
+    fn insert(&mut self, point: Vec2) {
+        let index = self.index(point);
+        self.cells[index] = point;
     }
 
-    fn neighbouring_points(&self, point: Vec2) -> Vec<Vec2> {
-        let cell = self.cell(point);
+    /// Whether a point inside the bounding box is more than the radius away
+    /// from all points in the grid.
+    fn is_free(&self, point: Vec2) -> bool {
+        let center = self.index(point);
+        self.neighbourhood.iter().all(|&offset| {
+            let neighbour = self.cells[center.wrapping_add_signed(offset)];
+            (point - neighbour).norm_square() > self.radius_square
+        })
+    }
+}
 
-        let center_ix = cell.0 as isize;
-        let center_iy = cell.1 as isize;
+#[cfg(test)]
+mod tests {
+    use crate::geometry::*;
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
 
-        // 25 is a 5*5 square. We don’t actually need to look at the corners so strictly
-        // speaking 21 would be enough, which I will implement later (AKA, not going to).
-        let mut result = Vec::with_capacity(25);
-        for ix_delta in -2 ..= 2 {
-            let ix = center_ix + ix_delta;
-            let x_out_of_bounds = ix < 0 || ix > self.size_x as isize - 1;
-            if x_out_of_bounds {
-                continue;
+    fn square(min: f64, max: f64) -> Polygon {
+        Polygon::from_points(vec![
+            Vec2::xy(min, min),
+            Vec2::xy(max, min),
+            Vec2::xy(max, max),
+            Vec2::xy(min, max),
+        ])
+    }
+
+    fn assert_spaced(points: &[Vec2], radius: f64) {
+        for (i, p) in points.iter().enumerate() {
+            for q in &points[i + 1 ..] {
+                assert!((*p - *q).norm() > radius);
             }
-            for iy_delta in -2 ..= 2 {
-                let iy = center_iy + iy_delta;
-                let y_out_of_bounds = iy < 0 || iy > self.size_y as isize - 1;
-                if y_out_of_bounds {
-                    continue;
-                }
+        }
+    }
 
-                // The corners of the 5*5 square are too far away to be reached
-                // within a distance of radius r from the center tile, so we
-                // exclude them here.
-                let is_corner = (ix_delta * iy_delta).abs() == 4;
-                if is_corner {
-                    continue;
-                }
+    #[test]
+    fn points_stay_inside_the_shape() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let triangle = Triangle::new(Vec2::xy(0., 0.), Vec2::xy(100., 0.), Vec2::xy(0., 100.));
+        let points = poisson_disc(&mut rng, triangle, 5., 20);
+        assert!(points.len() > 100);
+        assert!(points.iter().all(|p| triangle.contains(*p)));
+        assert_spaced(&points, 5.);
+    }
+
+    #[test]
+    fn seed_inside_when_center_is_outside() {
+        let mut rng = SmallRng::seed_from_u64(1);
+        let frame = PolygonWithHoles::new(square(0., 100.), vec![square(20., 80.)]);
+        let points = poisson_disc(&mut rng, &frame, 4., 20);
+        assert!(points.len() > 100);
+        assert!(points.iter().all(|p| frame.contains(*p)));
+        assert_spaced(&points, 4.);
+    }
+
+    #[test]
+    fn circle_region() {
+        let mut rng = SmallRng::seed_from_u64(2);
+        let circle = Circle::new(Vec2::xy(50., 50.), 50.);
+        let points = poisson_disc(&mut rng, circle, 5., 20);
+        assert!(points.iter().all(|p| circle.contains(*p)));
+        // Fills the circle up to its boundary.
+        assert!(points.iter().any(|p| (*p - circle.center).norm() > 45.));
+    }
+
+    #[test]
+    fn empty_region() {
+        let mut rng = SmallRng::seed_from_u64(3);
+        let line = Polygon::from_points(vec![Vec2::xy(0., 0.), Vec2::xy(100., 100.)]);
+        assert!(poisson_disc(&mut rng, line, 5., 20).is_empty());
+    }
 
-                if let Some(p) = self[(ix as usize, iy as usize)] {
-                    result.push(p)
-                };
+    #[test]
+    fn variable_radius_spacing() {
+        let mut rng = SmallRng::seed_from_u64(4);
+        let radius = |p: Vec2| 2. + p.x / 10.;
+        let region = (Vec2::xy(0., 0.), Vec2::xy(100., 100.)).bb();
+        let points = poisson_disc_variable(&mut rng, region, radius, 20);
+        for (i, &p) in points.iter().enumerate() {
+            assert!(region.contains(p));
+            for &q in &points[i + 1 ..] {
+                assert!((p - q).norm() >= (radius(p) + radius(q)) / 2.);
             }
         }
-        result
+        let left = points.iter().filter(|p| p.x < 50.).count();
+        assert!(left > 2 * (points.len() - left));
     }
-}
 
-impl Index<(usize, usize)> for Grid {
-    type Output = Option<Vec2>;
+    #[test]
+    fn variable_radius_is_reproducible() {
+        let run = |seed| {
+            let mut rng = SmallRng::seed_from_u64(seed);
+            let circle = Circle::new(Vec2::xy(50., 50.), 50.);
+            poisson_disc_variable(&mut rng, circle, |p| 3. + (p.y / 20.).sin(), 20)
+        };
+        assert_eq!(run(5), run(5));
+        assert_ne!(run(5), run(6));
+    }
 
-    fn index(&self, (ix, iy): (usize, usize)) -> &Self::Output {
-        &self.vec[iy * self.size_y + ix]
+    #[test]
+    fn constant_radius_matches_fixed_spacing() {
+        let mut rng = SmallRng::seed_from_u64(7);
+        let region = (Vec2::xy(-50., 20.), Vec2::xy(50., 60.)).bb();
+        let points = poisson_disc_variable(&mut rng, region, |_| 5., 20);
+        assert!(points.len() > 80);
+        assert_spaced(&points, 5. - 1e-9);
+        assert!(points.iter().all(|p| region.contains(*p)));
+    }
+
+    #[test]
+    fn variable_radius_close_to_zero() {
+        // The radius nearly vanishes at a corner that the grid estimate
+        // samples, but the grid stays small.
+        let mut rng = SmallRng::seed_from_u64(12);
+        let region = square(0., 100.);
+        let radius = |p: Vec2| if p == Vec2::xy(0., 0.) { 1e-12 } else { 5. };
+        let points = poisson_disc_variable(&mut rng, &region, radius, 10);
+        assert!(points.len() > 100);
+        assert!(points.iter().all(|p| region.contains(*p)));
+    }
+
+    #[test]
+    fn builder_keeps_initial_points_and_avoids_obstacles() {
+        let mut rng = SmallRng::seed_from_u64(8);
+        let region = (Vec2::xy(0., 0.), Vec2::xy(100., 100.)).bb();
+        let existing = vec![Vec2::xy(10., 10.), Vec2::xy(10.5, 10.), Vec2::xy(90., 90.)];
+        let obstacle = Circle::new(Vec2::xy(50., 50.), 20.);
+        let triangle = Triangle::new(Vec2::xy(0., 100.), Vec2::xy(30., 100.), Vec2::xy(0., 70.));
+        let new: Vec<Vec2> = PoissonDisc::new(region, 5.)
+            .initial_points(existing.clone())
+            .obstacle(obstacle)
+            .obstacle(Polygon::from(triangle))
+            .sample(&mut rng)
+            .collect();
+        assert!(new.len() > 100);
+        assert!(new.iter().all(|p| !existing.contains(p)));
+        assert!(new
+            .iter()
+            .all(|p| !obstacle.contains(*p) && !triangle.contains(*p)));
+        assert_spaced(&new, 5.);
+        for p in &new {
+            assert!(existing.iter().all(|q| (*p - *q).norm() > 5.));
+        }
     }
-}
 
-impl IndexMut<(usize, usize)> for Grid {
-    fn index_mut(&mut self, (ix, iy): (usize, usize)) -> &mut Self::Output {
-        &mut self.vec[iy * self.size_y + ix]
+    #[test]
+    fn builder_is_lazy_and_reproducible() {
+        let region = Circle::new(Vec2::xy(0., 0.), 30.);
+        let run = |n| {
+            let mut rng = SmallRng::seed_from_u64(9);
+            PoissonDisc::new(region, 3.)
+                .k(10)
+                .sample(&mut rng)
+                .take(n)
+                .collect::<Vec<_>>()
+        };
+        let all = run(usize::MAX);
+        assert!(all.len() > 50);
+        assert_eq!(all[0], region.center);
+        assert_eq!(run(10), all[.. 10]);
+        assert_spaced(&all, 3.);
+    }
+
+    #[test]
+    fn periodic_tiles_seamlessly() {
+        let mut rng = SmallRng::seed_from_u64(10);
+        let region = (Vec2::xy(-30., 10.), Vec2::xy(70., 60.)).bb();
+        let radius = 4.;
+        let points = poisson_disc_periodic(&mut rng, region, radius, 20);
+        assert!(points.len() > 100);
+        assert!(points.iter().all(|p| region.contains(*p)));
+
+        let period = region.max() - region.min();
+        let tiled: Vec<Vec2> = [(0., 0.), (1., 0.), (0., 1.), (1., 1.)]
+            .iter()
+            .flat_map(|&(i, j)| {
+                points
+                    .iter()
+                    .map(move |p| *p + Vec2::xy(i * period.x, j * period.y))
+            })
+            .collect();
+        assert_spaced(&tiled, radius);
+    }
+
+    #[test]
+    #[should_panic(expected = "radius must be positive and finite")]
+    fn zero_radius_is_rejected() {
+        // The grid would need infinitely many cells.
+        let mut rng = SmallRng::seed_from_u64(11);
+        poisson_disc(&mut rng, square(0., 100.), 0., 20);
+    }
+
+    /// Minimum distance and containment for many random regions, including
+    /// negative coordinates and extreme aspect ratios.
+    #[test]
+    fn property_random_regions() {
+        let mut rng = SmallRng::seed_from_u64(11);
+        for _ in 0 .. 100 {
+            let min = Vec2::xy(
+                rng.gen_range(-1000. .. 1000.),
+                rng.gen_range(-1000. .. 1000.),
+            );
+            let extent = Vec2::xy(rng.gen_range(1. .. 300.), rng.gen_range(1. .. 300.));
+            let region = (min, min + extent).bb();
+            let radius = rng.gen_range(0.05 .. 0.5) * extent.x.max(extent.y) / 5.;
+
+            let points = poisson_disc(&mut rng, region, radius, 20);
+            assert!(!points.is_empty());
+            assert!(points.iter().all(|p| region.contains(*p)), "{region:?}");
+            assert_spaced(&points, radius);
+
+            let circle = Circle::new(min, extent.x / 2.);
+            let points = poisson_disc(&mut rng, circle, radius, 20);
+            assert!(points.iter().all(|p| circle.contains(*p)), "{circle:?}");
+            assert_spaced(&points, radius);
+        }
     }
 }
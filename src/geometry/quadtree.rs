@@ -0,0 +1,213 @@
+use crate::geometry::core::*;
+
+/// Leaves are split once they hold more items than this.
+const NODE_CAPACITY: usize = 8;
+/// Limit for splitting, so that many items at the same place do not recurse
+/// forever.
+const MAX_DEPTH: usize = 16;
+
+/// Quadtree over items with a bounding box, for finding the items that might
+/// overlap an area.
+///
+/// Each item is stored in the smallest node that contains it completely, so
+/// items straddling a split stay in the parent. Items outside of the tree’s
+/// bounding box are kept in the root and still found by all queries, only
+/// slower.
+pub struct Quadtree<T> {
+    bb: BB,
+    root: Node,
+    items: Vec<T>,
+    bbs: Vec<BB>,
+}
+
+enum Node {
+    Leaf(Vec<usize>),
+    Branch {
+        items: Vec<usize>,
+        children: Box<[Node; 4]>,
+    },
+}
+
+impl<T: HasBB> Quadtree<T> {
+    pub fn new(bb: impl HasBB) -> Self {
+        Quadtree {
+            bb: bb.bb(),
+            root: Node::Leaf(Vec::new()),
+            items: Vec::new(),
+            bbs: Vec::new(),
+        }
+    }
+
+    pub fn insert(&mut self, item: T) {
+        let bb = item.bb();
+        self.items.push(item);
+        self.bbs.push(bb);
+        self.root
+            .insert(self.items.len() - 1, &self.bbs, self.bb, 0);
+    }
+
+    /// Items whose bounding box overlaps the given one. The items themselves
+    /// might still be apart.
+    pub fn overlapping(&self, bb: BB) -> impl Iterator<Item = &T> + '_ {
+        let mut stack = vec![(&self.root, self.bb)];
+        let mut current: &[usize] = &[];
+        std::iter::from_fn(move || loop {
+            if let Some((&i, rest)) = current.split_first() {
+                current = rest;
+                if self.bbs[i].overlaps(bb) {
+                    return Some(&self.items[i]);
+                }
+                continue;
+            }
+            let (node, node_bb) = stack.pop()?;
+            match node {
+                Node::Leaf(items) => current = items,
+                Node::Branch { items, children } => {
+                    current = items;
+                    for (child, child_bb) in children.iter().zip(quadrants(node_bb)) {
+                        if child_bb.overlaps(bb) {
+                            stack.push((child, child_bb));
+                        }
+                    }
+                }
+            }
+        })
+    }
+}
+
+impl<T> Quadtree<T> {
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// All items, in insertion order.
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    /// Areas of the leaves, to visualize how the tree is split.
+    pub fn leaves(&self) -> Vec<BB> {
+        let mut result = Vec::new();
+        let mut stack = vec![(&self.root, self.bb)];
+        while let Some((node, bb)) = stack.pop() {
+            match node {
+                Node::Leaf(_) => result.push(bb),
+                Node::Branch { children, .. } => stack.extend(children.iter().zip(quadrants(bb))),
+            }
+        }
+        result
+    }
+}
+
+impl<T: HasBB> Extend<T> for Quadtree<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, items: I) {
+        for item in items {
+            self.insert(item);
+        }
+    }
+}
+
+impl Node {
+    fn insert(&mut self, i: usize, bbs: &[BB], bb: BB, depth: usize) {
+        match self {
+            Node::Leaf(items) => {
+                items.push(i);
+                if items.len() > NODE_CAPACITY && depth < MAX_DEPTH {
+                    let items = std::mem::take(items);
+                    *self = Node::Branch {
+                        items: Vec::new(),
+                        children: Box::new(std::array::from_fn(|_| Node::Leaf(Vec::new()))),
+                    };
+                    for i in items {
+                        self.insert(i, bbs, bb, depth);
+                    }
+                }
+            }
+            Node::Branch { items, children } => {
+                match quadrants(bb)
+                    .into_iter()
+                    .position(|quadrant| bbs[i].is_inside(quadrant))
+                {
+                    Some(q) => children[q].insert(i, bbs, quadrants(bb)[q], depth + 1),
+                    None => items.push(i),
+                }
+            }
+        }
+    }
+}
+
+fn quadrants(bb: BB) -> [BB; 4] {
+    let (min, center, max) = (bb.min(), bb.center(), bb.max());
+    [
+        (min, center).bb(),
+        (Vec2::xy(center.x, min.y), Vec2::xy(max.x, center.y)).bb(),
+        (Vec2::xy(min.x, center.y), Vec2::xy(center.x, max.y)).bb(),
+        (center, max).bb(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::*;
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+    fn random_circles(rng: &mut SmallRng, n: usize) -> Vec<Circle> {
+        (0 .. n)
+            .map(|_| {
+                Circle::new(
+                    Vec2::xy(rng.gen_range(0. .. 100.), rng.gen_range(0. .. 100.)),
+                    rng.gen_range(0.1 .. 5.),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn overlap_queries_match_brute_force() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let circles = random_circles(&mut rng, 2000);
+        let mut tree = Quadtree::new((Vec2::xy(0., 0.), Vec2::xy(100., 100.)));
+        tree.extend(circles.iter().copied());
+        assert_eq!(tree.len(), 2000);
+        assert!(tree.leaves().len() > 100);
+
+        for query in random_circles(&mut rng, 100) {
+            let bb = query.bb();
+            let expected: Vec<&Circle> = circles.iter().filter(|c| c.bb().overlaps(bb)).collect();
+            let mut found: Vec<&Circle> = tree.overlapping(bb).collect();
+            found.sort_by_key(|c| circles.iter().position(|other| other == *c));
+            assert_eq!(expected, found);
+        }
+    }
+
+    #[test]
+    fn items_outside_are_still_found() {
+        let mut tree = Quadtree::new((Vec2::xy(0., 0.), Vec2::xy(10., 10.)));
+        let far = Line::from_to(Vec2::xy(-20., -20.), Vec2::xy(-15., -10.));
+        tree.insert(far);
+        for x in 0 .. 50 {
+            let x = x as f64 / 5.;
+            tree.insert(Line::from_to(Vec2::xy(x, 0.), Vec2::xy(x, 1.)));
+        }
+        let query = (Vec2::xy(-16., -16.), Vec2::xy(-15., -15.)).bb();
+        assert_eq!(tree.overlapping(query).collect::<Vec<_>>(), vec![&far]);
+        assert_eq!(tree.overlapping(Vec2::xy(5., 0.5).bb()).count(), 1);
+    }
+
+    #[test]
+    fn leaves_tile_the_area() {
+        let mut rng = SmallRng::seed_from_u64(1);
+        let bb = (Vec2::xy(0., 0.), Vec2::xy(100., 100.)).bb();
+        let mut tree = Quadtree::new(bb);
+        // Identical points would split forever without a depth limit.
+        tree.extend(std::iter::repeat_n(Vec2::xy(1., 1.), 100));
+        tree.extend(random_circles(&mut rng, 100).into_iter().map(|c| c.center));
+        let leaves = tree.leaves();
+        assert!((leaves.iter().map(|leaf| leaf.area()).sum::<f64>() - bb.area()).abs() < 1e-6);
+        assert!(leaves.iter().all(|leaf| leaf.is_inside(bb)));
+    }
+}
@@ -0,0 +1,183 @@
+use crate::geometry::core::*;
+
+/// Chaikin corner cutting for an open path. Each iteration replaces every
+/// corner by two points at `ratio` and `1 - ratio` along the adjacent edges;
+/// the end points stay in place.
+///
+/// `ratio` is clamped to (0, 0.5]; the classic value is 0.25. The result
+/// approaches a quadratic B-spline.
+pub fn chaikin(points: &[Vec2], ratio: f64, iterations: usize) -> Vec<Vec2> {
+    let ratio = ratio.clamp(f64::EPSILON, 0.5);
+    let mut current = points.to_vec();
+    for _ in 0 .. iterations {
+        if current.len() < 3 {
+            break;
+        }
+        let n = current.len();
+        let mut next = Vec::with_capacity(2 * n);
+        next.push(current[0]);
+        for (i, edge) in current.windows(2).enumerate() {
+            let (a, b) = cut(edge[0], edge[1], ratio);
+            if i > 0 {
+                next.push(a);
+            }
+            if i < n - 2 {
+                next.push(b);
+            }
+        }
+        next.push(current[n - 1]);
+        current = next;
+    }
+    current
+}
+
+/// Chaikin corner cutting for a closed polygon, see [`chaikin`].
+pub fn chaikin_closed(polygon: &Polygon, ratio: f64, iterations: usize) -> Polygon {
+    let ratio = ratio.clamp(f64::EPSILON, 0.5);
+    let mut current: Vec<Vec2> = polygon.points().copied().collect();
+    for _ in 0 .. iterations {
+        if current.len() < 3 {
+            break;
+        }
+        current = Polygon::from_points(current)
+            .edges()
+            .flat_map(|edge| {
+                let (a, b) = cut(edge.start, edge.end, ratio);
+                [a, b]
+            })
+            .collect();
+    }
+    Polygon::from_points(current)
+}
+
+fn cut(a: Vec2, b: Vec2, ratio: f64) -> (Vec2, Vec2) {
+    (a + (b - a) * ratio, a + (b - a) * (1. - ratio))
+}
+
+/// Interpolating four-point subdivision (Dyn, Levin, Gregory) for an open
+/// path. All original points are kept, and each iteration inserts a new point
+/// between every adjacent pair. The limit curve is smooth.
+pub fn four_point(points: &[Vec2], iterations: usize) -> Vec<Vec2> {
+    let mut current = points.to_vec();
+    for _ in 0 .. iterations {
+        let n = current.len();
+        if n < 3 {
+            break;
+        }
+        // Reflect the neighbours at the ends to get virtual outer points.
+        let before = current[0] * 2. - current[1];
+        let after = current[n - 1] * 2. - current[n - 2];
+        let at = |i: isize| match i {
+            -1 => before,
+            i if i as usize == n => after,
+            i => current[i as usize],
+        };
+        let mut next = Vec::with_capacity(2 * n - 1);
+        for i in 0 .. n as isize - 1 {
+            next.push(at(i));
+            next.push(four_point_rule(at(i - 1), at(i), at(i + 1), at(i + 2)));
+        }
+        next.push(current[n - 1]);
+        current = next;
+    }
+    current
+}
+
+/// Four-point subdivision for a closed polygon, see [`four_point`].
+pub fn four_point_closed(polygon: &Polygon, iterations: usize) -> Polygon {
+    let mut current: Vec<Vec2> = polygon.points().copied().collect();
+    for _ in 0 .. iterations {
+        let n = current.len();
+        if n < 3 {
+            break;
+        }
+        current = (0 .. n)
+            .flat_map(|i| {
+                let inserted = four_point_rule(
+                    current[(i + n - 1) % n],
+                    current[i],
+                    current[(i + 1) % n],
+                    current[(i + 2) % n],
+                );
+                [current[i], inserted]
+            })
+            .collect();
+    }
+    Polygon::from_points(current)
+}
+
+fn four_point_rule(p0: Vec2, p1: Vec2, p2: Vec2, p3: Vec2) -> Vec2 {
+    ((p1 + p2) * 9. - p0 - p3) / 16.
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::*;
+
+    fn square() -> Polygon {
+        Polygon::from_points(vec![
+            Vec2::xy(0., 0.),
+            Vec2::xy(100., 0.),
+            Vec2::xy(100., 100.),
+            Vec2::xy(0., 100.),
+        ])
+    }
+
+    fn reversed(polygon: &Polygon) -> Polygon {
+        let mut points: Vec<Vec2> = polygon.points().copied().collect();
+        points.reverse();
+        Polygon::from_points(points)
+    }
+
+    #[test]
+    fn chaikin_closed_cuts_corners() {
+        let smooth = chaikin_closed(&square(), 0.25, 1);
+        assert_eq!(smooth.points().count(), 8);
+        // Each corner loses a right triangle with legs of 25.
+        assert!((smooth.area() - (10_000. - 4. * 25. * 25. / 2.)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn chaikin_closed_preserves_orientation() {
+        for polygon in [square(), reversed(&square())] {
+            let smooth = chaikin_closed(&polygon, 0.25, 3);
+            assert_eq!(smooth.orientation(), polygon.orientation());
+        }
+    }
+
+    #[test]
+    fn chaikin_open_keeps_end_points() {
+        let path = vec![Vec2::xy(0., 0.), Vec2::xy(10., 10.), Vec2::xy(20., 0.)];
+        let smooth = chaikin(&path, 0.25, 2);
+        assert_eq!(smooth.first(), path.first());
+        assert_eq!(smooth.last(), path.last());
+        assert!(smooth.iter().all(|p| p.y < 10.));
+    }
+
+    #[test]
+    fn four_point_interpolates() {
+        let path = vec![
+            Vec2::xy(0., 0.),
+            Vec2::xy(10., 10.),
+            Vec2::xy(20., 0.),
+            Vec2::xy(30., 10.),
+        ];
+        let smooth = four_point(&path, 1);
+        assert_eq!(smooth.len(), 7);
+        for (i, p) in path.iter().enumerate() {
+            assert_eq!(smooth[2 * i], *p);
+        }
+    }
+
+    #[test]
+    fn four_point_closed_preserves_orientation_and_points() {
+        for polygon in [square(), reversed(&square())] {
+            let smooth = four_point_closed(&polygon, 2);
+            assert_eq!(smooth.points().count(), 16);
+            assert_eq!(smooth.orientation(), polygon.orientation());
+            for p in polygon.points() {
+                assert!(smooth.points().any(|q| q == p));
+            }
+        }
+    }
+}
@@ -0,0 +1,343 @@
+use crate::geometry::core::*;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::BuildHasherDefault;
+
+/// Things with a location, so they can be put into a [`SpatialIndex`].
+pub trait HasPosition {
+    fn position(&self) -> Vec2;
+}
+
+impl HasPosition for Vec2 {
+    fn position(&self) -> Vec2 {
+        *self
+    }
+}
+
+impl HasPosition for Circle {
+    fn position(&self) -> Vec2 {
+        self.center
+    }
+}
+
+/// A point with attached data.
+impl<T> HasPosition for (Vec2, T) {
+    fn position(&self) -> Vec2 {
+        self.0
+    }
+}
+
+impl<T: HasPosition + ?Sized> HasPosition for &T {
+    fn position(&self) -> Vec2 {
+        (**self).position()
+    }
+}
+
+/// Handle of an item in a [`SpatialIndex`]. Handles of removed items are
+/// reused.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct ItemId(usize);
+
+type Cell = (i64, i64);
+
+/// Unbounded uniform grid for finding items by their location.
+///
+/// Queries are fastest when the cell size is around the typical query radius,
+/// or the typical distance between items for nearest-neighbour queries. Only
+/// cells with items take up memory.
+pub struct SpatialIndex<T> {
+    cell_size: f64,
+    // The default hasher has fixed keys, so queries yield items in the same
+    // order on every run.
+    cells: HashMap<Cell, Vec<ItemId>, BuildHasherDefault<DefaultHasher>>,
+    items: Vec<Option<T>>,
+    free: Vec<ItemId>,
+    len: usize,
+    /// Lowest and highest cell coordinates ever occupied. Queries never have to
+    /// look outside.
+    extent: Option<(Cell, Cell)>,
+}
+
+impl<T: HasPosition> SpatialIndex<T> {
+    /// Panics unless the cell size is positive and finite.
+    pub fn new(cell_size: f64) -> Self {
+        assert!(
+            cell_size > 0. && cell_size.is_finite(),
+            "cell size must be positive and finite"
+        );
+        SpatialIndex {
+            cell_size,
+            cells: HashMap::default(),
+            items: Vec::new(),
+            free: Vec::new(),
+            len: 0,
+            extent: None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, id: ItemId) -> Option<&T> {
+        self.items.get(id.0)?.as_ref()
+    }
+
+    /// All items, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (ItemId, &T)> + '_ {
+        self.items
+            .iter()
+            .enumerate()
+            .filter_map(|(i, item)| Some((ItemId(i), item.as_ref()?)))
+    }
+
+    pub fn insert(&mut self, item: T) -> ItemId {
+        let cell = self.cell(item.position());
+        let id = match self.free.pop() {
+            Some(id) => {
+                self.items[id.0] = Some(item);
+                id
+            }
+            None => {
+                self.items.push(Some(item));
+                ItemId(self.items.len() - 1)
+            }
+        };
+        self.cells.entry(cell).or_default().push(id);
+        self.extent = Some(match self.extent {
+            None => (cell, cell),
+            Some((lo, hi)) => (
+                (lo.0.min(cell.0), lo.1.min(cell.1)),
+                (hi.0.max(cell.0), hi.1.max(cell.1)),
+            ),
+        });
+        self.len += 1;
+        id
+    }
+
+    pub fn remove(&mut self, id: ItemId) -> Option<T> {
+        let item = self.items.get_mut(id.0)?.take()?;
+        let cell = self.cell(item.position());
+        if let Some(ids) = self.cells.get_mut(&cell) {
+            ids.retain(|other| *other != id);
+            if ids.is_empty() {
+                self.cells.remove(&cell);
+            }
+        }
+        self.free.push(id);
+        self.len -= 1;
+        Some(item)
+    }
+
+    /// Items at most `radius` away from the center.
+    pub fn within_radius(
+        &self,
+        center: Vec2,
+        radius: f64,
+    ) -> impl Iterator<Item = (ItemId, &T)> + '_ {
+        let offset = Vec2::xy(radius, radius);
+        self.items_in_cells(self.cell(center - offset), self.cell(center + offset))
+            .filter(move |(_, item)| (item.position() - center).norm_square() <= radius * radius)
+    }
+
+    /// Items inside the bounding box, including its edges.
+    pub fn within_bb(&self, bb: BB) -> impl Iterator<Item = (ItemId, &T)> + '_ {
+        self.items_in_cells(self.cell(bb.min()), self.cell(bb.max()))
+            .filter(move |(_, item)| bb.contains(item.position()))
+    }
+
+    pub fn nearest(&self, point: Vec2) -> Option<(ItemId, &T)> {
+        self.k_nearest(point, 1).into_iter().next()
+    }
+
+    /// The `k` items closest to the point, nearest first. Ties are broken by
+    /// [`ItemId`].
+    pub fn k_nearest(&self, point: Vec2, k: usize) -> Vec<(ItemId, &T)> {
+        let Some((lo, hi)) = self.extent else {
+            return Vec::new();
+        };
+        if k == 0 {
+            return Vec::new();
+        }
+        let center = self.cell(point);
+        let mut found: Vec<(f64, ItemId)> = Vec::new();
+        let by_distance =
+            |a: &(f64, ItemId), b: &(f64, ItemId)| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1));
+
+        // Search in square rings of cells around the point until the rings
+        // are further away than the k-th nearest item found so far.
+        for ring in 0 .. {
+            for cell in ring_cells(center, ring, (lo, hi)) {
+                for &id in self.cells.get(&cell).into_iter().flatten() {
+                    let distance =
+                        (self.items[id.0].as_ref().unwrap().position() - point).norm_square();
+                    found.push((distance, id));
+                }
+            }
+
+            let covers_extent = center.0 - ring <= lo.0
+                && center.1 - ring <= lo.1
+                && center.0 + ring >= hi.0
+                && center.1 + ring >= hi.1;
+            if covers_extent {
+                break;
+            }
+            if found.len() >= k {
+                found.select_nth_unstable_by(k - 1, by_distance);
+                // Every cell of the next ring is at least this far away.
+                let reach = ring as f64 * self.cell_size;
+                if found[k - 1].0 <= reach * reach {
+                    break;
+                }
+            }
+        }
+
+        found.sort_by(by_distance);
+        found
+            .into_iter()
+            .take(k)
+            .map(|(_, id)| (id, self.items[id.0].as_ref().unwrap()))
+            .collect()
+    }
+
+    fn cell(&self, point: Vec2) -> Cell {
+        (
+            (point.x / self.cell_size).floor() as i64,
+            (point.y / self.cell_size).floor() as i64,
+        )
+    }
+
+    /// Items in the rectangle of cells between min and max, inclusive.
+    fn items_in_cells(&self, min: Cell, max: Cell) -> impl Iterator<Item = (ItemId, &T)> + '_ {
+        // Empty ranges unless the rectangle overlaps the occupied extent.
+        let ((x0, y0), (x1, y1)) = match self.extent {
+            Some((lo, hi)) => (
+                (min.0.max(lo.0), min.1.max(lo.1)),
+                (max.0.min(hi.0), max.1.min(hi.1)),
+            ),
+            None => ((0, 0), (-1, -1)),
+        };
+        (y0 ..= y1)
+            .flat_map(move |y| (x0 ..= x1).map(move |x| (x, y)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .map(|&id| (id, self.items[id.0].as_ref().unwrap()))
+    }
+}
+
+impl<T: HasPosition> Extend<T> for SpatialIndex<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, items: I) {
+        for item in items {
+            self.insert(item);
+        }
+    }
+}
+
+/// Cells at Chebyshev distance `ring` from the center, clipped to the extent.
+fn ring_cells(center: Cell, ring: i64, (lo, hi): (Cell, Cell)) -> impl Iterator<Item = Cell> {
+    let (cx, cy) = center;
+    let xs = (cx - ring).max(lo.0) ..= (cx + ring).min(hi.0);
+    let ys = (cy - ring + 1).max(lo.1) ..= (cy + ring - 1).min(hi.1);
+    let rows = [cy - ring, cy + ring]
+        .into_iter()
+        .take(if ring == 0 { 1 } else { 2 })
+        .filter(move |y| (lo.1 ..= hi.1).contains(y))
+        .flat_map(move |y| xs.clone().map(move |x| (x, y)));
+    let columns = [cx - ring, cx + ring]
+        .into_iter()
+        .take(if ring == 0 { 0 } else { 2 })
+        .filter(move |x| (lo.0 ..= hi.0).contains(x))
+        .flat_map(move |x| ys.clone().map(move |y| (x, y)));
+    rows.chain(columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::*;
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+    fn random_points(rng: &mut SmallRng, n: usize) -> Vec<Vec2> {
+        (0 .. n)
+            .map(|_| Vec2::xy(rng.gen_range(-50. .. 150.), rng.gen_range(-50. .. 50.)))
+            .collect()
+    }
+
+    #[test]
+    fn queries_match_brute_force() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let points = random_points(&mut rng, 1000);
+        let mut index = SpatialIndex::new(7.);
+        index.extend(points.iter().copied());
+
+        for query in random_points(&mut rng, 100) {
+            let radius = rng.gen_range(0. .. 30.);
+            let mut expected: Vec<Vec2> = points
+                .iter()
+                .copied()
+                .filter(|p| (*p - query).norm() <= radius)
+                .collect();
+            let mut actual: Vec<Vec2> = index
+                .within_radius(query, radius)
+                .map(|(_, p)| *p)
+                .collect();
+            let by_x = |a: &Vec2, b: &Vec2| a.x.total_cmp(&b.x);
+            expected.sort_by(by_x);
+            actual.sort_by(by_x);
+            assert_eq!(expected, actual);
+
+            let bb = (query, query + Vec2::xy(radius, radius / 2.)).bb();
+            let expected = points.iter().filter(|p| bb.contains(**p)).count();
+            assert_eq!(index.within_bb(bb).count(), expected);
+        }
+    }
+
+    #[test]
+    fn k_nearest_matches_brute_force() {
+        let mut rng = SmallRng::seed_from_u64(1);
+        let points = random_points(&mut rng, 500);
+        let mut index = SpatialIndex::new(5.);
+        index.extend(points.iter().copied());
+
+        // Some query points are far outside the occupied area.
+        for query in random_points(&mut rng, 50).into_iter().map(|p| p * 3.) {
+            let mut distances: Vec<f64> = points.iter().map(|p| (*p - query).norm()).collect();
+            distances.sort_by(f64::total_cmp);
+            let found: Vec<f64> = index
+                .k_nearest(query, 10)
+                .into_iter()
+                .map(|(_, p)| (*p - query).norm())
+                .collect();
+            assert_eq!(found, distances[.. 10]);
+        }
+        assert_eq!(index.k_nearest(Vec2::xy(0., 0.), 1000).len(), 500);
+    }
+
+    #[test]
+    fn insert_and_remove() {
+        let mut index = SpatialIndex::new(1.);
+        assert!(index.nearest(Vec2::xy(0., 0.)).is_none());
+
+        let a = index.insert((Vec2::xy(0., 0.), "a"));
+        let b = index.insert((Vec2::xy(5., 5.), "b"));
+        assert_eq!(index.len(), 2);
+        assert_eq!(
+            index.nearest(Vec2::xy(1., 1.)),
+            Some((a, &(Vec2::xy(0., 0.), "a")))
+        );
+
+        assert_eq!(index.remove(a), Some((Vec2::xy(0., 0.), "a")));
+        assert_eq!(index.remove(a), None);
+        assert!(index.get(a).is_none());
+        assert_eq!(index.nearest(Vec2::xy(1., 1.)).map(|(id, _)| id), Some(b));
+        assert_eq!(index.within_radius(Vec2::xy(0., 0.), 2.).count(), 0);
+
+        let c = index.insert((Vec2::xy(-3., 2.), "c"));
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.get(c).map(|(_, name)| *name), Some("c"));
+        assert_eq!(index.iter().count(), 2);
+    }
+}
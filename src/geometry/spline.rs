@@ -0,0 +1,166 @@
+use crate::geometry::core::*;
+
+/// Parametrization of a Catmull–Rom spline.
+///
+/// Centripetal splines avoid the cusps and self-intersections the uniform
+/// variant produces for unevenly spaced points, and are usually what you want.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum CatmullRom {
+    Uniform,
+    Centripetal,
+    Chordal,
+}
+
+impl CatmullRom {
+    fn alpha(self) -> f64 {
+        match self {
+            CatmullRom::Uniform => 0.,
+            CatmullRom::Centripetal => 0.5,
+            CatmullRom::Chordal => 1.,
+        }
+    }
+}
+
+/// Smooth open curve through all points, one Bézier segment between each pair
+/// of adjacent points.
+///
+/// The end tangents are derived from reflecting the second (and second to
+/// last) point at the ends.
+pub fn catmull_rom(points: &[Vec2], parametrization: CatmullRom) -> Vec<CubicBezier> {
+    let points = without_duplicates(points);
+    let n = points.len();
+    if n < 2 {
+        return Vec::new();
+    }
+    let before = points[0] * 2. - points[1];
+    let after = points[n - 1] * 2. - points[n - 2];
+    (0 .. n - 1)
+        .map(|i| {
+            let p0 = if i == 0 { before } else { points[i - 1] };
+            let p3 = if i + 2 == n { after } else { points[i + 2] };
+            segment(p0, points[i], points[i + 1], p3, parametrization.alpha())
+        })
+        .collect()
+}
+
+/// Smooth closed curve through all points, including a segment from the last
+/// back to the first point.
+pub fn catmull_rom_closed(points: &[Vec2], parametrization: CatmullRom) -> Vec<CubicBezier> {
+    let mut points = without_duplicates(points);
+    if points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+    let n = points.len();
+    if n < 2 {
+        return Vec::new();
+    }
+    (0 .. n)
+        .map(|i| {
+            segment(
+                points[(i + n - 1) % n],
+                points[i],
+                points[(i + 1) % n],
+                points[(i + 2) % n],
+                parametrization.alpha(),
+            )
+        })
+        .collect()
+}
+
+fn without_duplicates(points: &[Vec2]) -> Vec<Vec2> {
+    let mut result = points.to_vec();
+    result.dedup();
+    result
+}
+
+/// Bézier form of the spline segment between p1 and p2 (Yuksel et al.,
+/// “Parameterization and applications of Catmull–Rom curves”).
+fn segment(p0: Vec2, p1: Vec2, p2: Vec2, p3: Vec2, alpha: f64) -> CubicBezier {
+    let knot = |a: Vec2, b: Vec2| (b - a).norm().powf(alpha).max(1e-12);
+    let dt0 = knot(p0, p1);
+    let dt1 = knot(p1, p2);
+    let dt2 = knot(p2, p3);
+
+    let m1 = ((p1 - p0) / dt0 - (p2 - p0) / (dt0 + dt1) + (p2 - p1) / dt1) * dt1;
+    let m2 = ((p2 - p1) / dt1 - (p3 - p1) / (dt1 + dt2) + (p3 - p2) / dt2) * dt1;
+    CubicBezier::new(p1, p1 + m1 / 3., p2 - m2 / 3., p2)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::*;
+
+    fn zigzag() -> Vec<Vec2> {
+        vec![
+            Vec2::xy(0., 0.),
+            Vec2::xy(10., 10.),
+            Vec2::xy(12., 0.),
+            Vec2::xy(40., 10.),
+            Vec2::xy(41., 0.),
+        ]
+    }
+
+    #[test]
+    fn passes_through_points() {
+        for parametrization in [
+            CatmullRom::Uniform,
+            CatmullRom::Centripetal,
+            CatmullRom::Chordal,
+        ] {
+            let points = zigzag();
+            let segments = catmull_rom(&points, parametrization);
+            assert_eq!(segments.len(), points.len() - 1);
+            for (segment, pair) in segments.iter().zip(points.windows(2)) {
+                assert_eq!(segment.start, pair[0]);
+                assert_eq!(segment.end, pair[1]);
+            }
+        }
+    }
+
+    #[test]
+    fn smooth_joints() {
+        let segments = catmull_rom(&zigzag(), CatmullRom::Centripetal);
+        for pair in segments.windows(2) {
+            let incoming = pair[0].derivative(1.);
+            let outgoing = pair[1].derivative(0.);
+            assert!(incoming.cross(outgoing).abs() < 1e-9 * incoming.norm() * outgoing.norm());
+            assert!(incoming.dot(outgoing) > 0.);
+        }
+    }
+
+    #[test]
+    fn uniform_tangents() {
+        let points = zigzag();
+        let segments = catmull_rom(&points, CatmullRom::Uniform);
+        let expected = (points[2] - points[0]) / 2.;
+        assert!((segments[1].derivative(0.) - expected).norm() < 1e-9);
+    }
+
+    #[test]
+    fn closed() {
+        let points = vec![
+            Vec2::xy(0., 0.),
+            Vec2::xy(10., 0.),
+            Vec2::xy(10., 10.),
+            Vec2::xy(0., 10.),
+        ];
+        let segments = catmull_rom_closed(&points, CatmullRom::Centripetal);
+        assert_eq!(segments.len(), 4);
+        assert_eq!(segments[3].end, points[0]);
+    }
+
+    #[test]
+    fn duplicate_points_are_ignored() {
+        let points = vec![Vec2::xy(0., 0.), Vec2::xy(0., 0.), Vec2::xy(10., 0.)];
+        let segments = catmull_rom(&points, CatmullRom::Chordal);
+        assert_eq!(segments.len(), 1);
+        assert!(segments[0].control1.x.is_finite());
+    }
+
+    #[test]
+    fn transformable() {
+        let segments = catmull_rom(&zigzag(), CatmullRom::Centripetal);
+        let moved = segments.transform(Transformation::translate(Vec2::xy(5., 0.)));
+        assert_eq!(moved[0].start, Vec2::xy(5., 0.));
+    }
+}
@@ -0,0 +1,133 @@
+use crate::geometry::core::*;
+use crate::geometry::lloyd::*;
+use rand::Rng;
+
+/// Size of the dots of a stippling.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum DotSize {
+    /// All dots have the same radius.
+    Constant(f64),
+    /// Radius proportional to the density at the dot, reaching `max` at
+    /// density 1.
+    Density { max: f64 },
+}
+
+/// Weighted Voronoi stippling (Secord 2002): distribute `count` dots so that
+/// their local concentration follows the density.
+///
+/// The density should be in [0, 1], for example the darkness of an image. The
+/// dots start at random positions drawn from the density, and are then
+/// relaxed for `iterations` rounds of density-weighted Lloyd relaxation.
+///
+/// A region without area yields no dots. Positions are drawn by rejection
+/// sampling with a limited number of attempts, so a density that is close to
+/// 0 almost everywhere can yield fewer than `count` dots.
+///
+/// To stipple a photograph, load it with [`crate::draw::png::read_grayscale`]
+/// and pass the image as region and its darkness as density.
+pub fn stipple<R: Rng, Region: HasBB>(
+    rng: &mut R,
+    region: Region,
+    density: impl Fn(Vec2) -> f64,
+    count: usize,
+    iterations: usize,
+    dot_size: DotSize,
+) -> Vec<Circle> {
+    let bb = region.bb();
+    let (min, max) = (bb.min(), bb.max());
+    // Also catches the inverted box of an empty region.
+    if !(min.x < max.x && min.y < max.y) {
+        return Vec::new();
+    }
+    let density = |p: Vec2| density(p).clamp(0., 1.);
+
+    // Rejection sampling, so dark areas are populated from the start and the
+    // relaxation only has to even things out locally.
+    let max_attempts = 1000 * count;
+    let mut points = Vec::with_capacity(count);
+    for _ in 0 .. max_attempts {
+        if points.len() == count {
+            break;
+        }
+        let candidate = Vec2::xy(rng.gen_range(min.x .. max.x), rng.gen_range(min.y .. max.y));
+        if rng.gen::<f64>() < density(candidate) {
+            points.push(candidate);
+        }
+    }
+
+    let points = lloyd_relaxation_weighted(&points, bb, density, iterations, 0.);
+    points
+        .into_iter()
+        .map(|center| {
+            let radius = match dot_size {
+                DotSize::Constant(radius) => radius,
+                DotSize::Density { max } => max * density(center),
+            };
+            Circle::new(center, radius)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::*;
+    use rand::{rngs::SmallRng, SeedableRng};
+
+    fn region() -> BB {
+        (Vec2::xy(0., 0.), Vec2::xy(100., 100.)).bb()
+    }
+
+    #[test]
+    fn dots_follow_density() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let gradient = |p: Vec2| p.x / 100.;
+        let dots = stipple(&mut rng, region(), gradient, 500, 10, DotSize::Constant(1.));
+        assert_eq!(dots.len(), 500);
+        let right = dots.iter().filter(|dot| dot.center.x > 50.).count();
+        // Three quarters of the ink is on the right.
+        assert!(right > 325, "only {right} dots on the dark side");
+        assert!(dots.iter().all(|dot| dot.radius == 1.));
+    }
+
+    #[test]
+    fn dot_size_follows_density() {
+        let mut rng = SmallRng::seed_from_u64(1);
+        let gradient = |p: Vec2| p.x / 100.;
+        let dots = stipple(
+            &mut rng,
+            region(),
+            gradient,
+            100,
+            2,
+            DotSize::Density { max: 2. },
+        );
+        for dot in dots {
+            assert!((dot.radius - 2. * dot.center.x / 100.).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn seeded_results_are_reproducible() {
+        let run = |seed| {
+            let mut rng = SmallRng::seed_from_u64(seed);
+            stipple(&mut rng, region(), |_| 0.5, 50, 3, DotSize::Constant(1.))
+        };
+        assert_eq!(run(2), run(2));
+        assert_ne!(run(2), run(3));
+    }
+
+    #[test]
+    fn blank_density_yields_no_dots() {
+        let mut rng = SmallRng::seed_from_u64(4);
+        assert!(stipple(&mut rng, region(), |_| 0., 10, 3, DotSize::Constant(1.)).is_empty());
+    }
+
+    #[test]
+    fn empty_region_yields_no_dots() {
+        let mut rng = SmallRng::seed_from_u64(5);
+        let line = (Vec2::xy(0., 0.), Vec2::xy(100., 0.)).bb();
+        assert!(stipple(&mut rng, line, |_| 1., 10, 3, DotSize::Constant(1.)).is_empty());
+        let nothing: Vec<Vec2> = Vec::new();
+        assert!(stipple(&mut rng, nothing, |_| 1., 10, 3, DotSize::Constant(1.)).is_empty());
+    }
+}
@@ -0,0 +1,268 @@
+use crate::geometry::core::*;
+use crate::geometry::delaunay::*;
+
+/// Voronoi cell of a single site: all points of the region closer to the site
+/// than to any other.
+#[derive(Debug, PartialEq, Clone)]
+pub struct VoronoiCell {
+    pub site: Vec2,
+    /// Convex, positively oriented. Empty for sites outside the region, and
+    /// for all but the first of several sites at the same place.
+    pub polygon: Polygon,
+    /// Indices of the sites whose cells share an edge with this one.
+    pub neighbours: Vec<usize>,
+}
+
+/// Voronoi diagram of the sites, clipped to a convex region such as a [`BB`].
+///
+/// Returns one cell per site, in the order of the sites. The cells are built
+/// by cutting the region along the bisectors to the Delaunay neighbours, which
+/// only works for convex regions; debug builds check this.
+pub fn voronoi(sites: &[Vec2], region: impl Into<Polygon>) -> Vec<VoronoiCell> {
+    let mut region = region.into();
+    debug_assert!(region.is_convex(), "Voronoi region must be convex");
+    if region.orientation() == Orientation::Negative {
+        let mut points: Vec<Vec2> = region.points().copied().collect();
+        points.reverse();
+        region = Polygon::from_points(points);
+    }
+
+    // Sites at the same place share one cell, which goes to the first of them.
+    let mut by_position: Vec<usize> = (0 .. sites.len()).collect();
+    by_position.sort_by(|&i, &j| {
+        let (p, q) = (sites[i], sites[j]);
+        p.x.total_cmp(&q.x)
+            .then(p.y.total_cmp(&q.y))
+            .then(i.cmp(&j))
+    });
+    let mut first: Vec<usize> = (0 .. sites.len()).collect();
+    for pair in by_position.windows(2) {
+        if sites[pair[0]] == sites[pair[1]] {
+            first[pair[1]] = first[pair[0]];
+        }
+    }
+    let distinct = (0 .. sites.len()).filter(|&i| first[i] == i).count();
+
+    let triangulation = delaunay(sites);
+    let mut candidates = vec![Vec::new(); sites.len()];
+    if triangulation.triangle_count() > 0 {
+        for (a, b) in triangulation.edge_indices() {
+            let (a, b) = (first[a], first[b]);
+            candidates[a].push(b);
+            candidates[b].push(a);
+        }
+    } else {
+        // Collinear or fewer than three sites: every site may be a neighbour.
+        for (i, candidates) in candidates.iter_mut().enumerate() {
+            if first[i] == i {
+                candidates.extend((0 .. sites.len()).filter(|&j| j != i && first[j] == j));
+            }
+        }
+    }
+
+    let scale = region.bb().max() - region.bb().min();
+    let min_edge_length = 1e-12 * scale.norm();
+    sites
+        .iter()
+        .zip(candidates)
+        .enumerate()
+        .map(|(i, (&site, candidates))| {
+            if first[i] != i || (candidates.is_empty() && distinct > 1) {
+                return VoronoiCell {
+                    site,
+                    polygon: Polygon::from_points(Vec::new()),
+                    neighbours: Vec::new(),
+                };
+            }
+            let mut cell = Cell::new(&region);
+            for j in candidates {
+                cell.clip(site, sites[j], j);
+            }
+            let mut neighbours: Vec<usize> = cell
+                .edges()
+                .filter(|(line, _)| line.length() > min_edge_length)
+                .filter_map(|(_, label)| label)
+                .collect();
+            neighbours.sort_unstable();
+            neighbours.dedup();
+            VoronoiCell {
+                site,
+                polygon: Polygon::from_points(cell.points),
+                neighbours,
+            }
+        })
+        .collect()
+}
+
+/// Convex polygon that remembers which site’s bisector produced each edge.
+struct Cell {
+    points: Vec<Vec2>,
+    /// Label of the edge starting at the corresponding point; `None` for the
+    /// region boundary.
+    labels: Vec<Option<usize>>,
+}
+
+impl Cell {
+    fn new(region: &Polygon) -> Self {
+        let points: Vec<Vec2> = region.points().copied().collect();
+        let labels = vec![None; points.len()];
+        Cell { points, labels }
+    }
+
+    /// Keep only the half closer to `site` than to `other`.
+    fn clip(&mut self, site: Vec2, other: Vec2, label: usize) {
+        let normal = other - site;
+        if normal.norm_square() == 0. {
+            return;
+        }
+        let offset = normal.dot((site + other) / 2.);
+        let distance = |p: Vec2| normal.dot(p) - offset;
+
+        let n = self.points.len();
+        let mut points = Vec::with_capacity(n + 1);
+        let mut labels = Vec::with_capacity(n + 1);
+        for i in 0 .. n {
+            let (p, q) = (self.points[i], self.points[(i + 1) % n]);
+            let (dp, dq) = (distance(p), distance(q));
+            if dp <= 0. {
+                points.push(p);
+                labels.push(self.labels[i]);
+                if dq > 0. {
+                    points.push(p + (q - p) * (dp / (dp - dq)));
+                    labels.push(Some(label));
+                }
+            } else if dq <= 0. {
+                points.push(p + (q - p) * (dp / (dp - dq)));
+                labels.push(self.labels[i]);
+            }
+        }
+        self.points = points;
+        self.labels = labels;
+    }
+
+    fn edges(&self) -> impl Iterator<Item = (Line, Option<usize>)> + '_ {
+        let n = self.points.len();
+        (0 .. n).map(move |i| {
+            (
+                Line::from_to(self.points[i], self.points[(i + 1) % n]),
+                self.labels[i],
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::*;
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+    fn region() -> BB {
+        (Vec2::xy(0., 0.), Vec2::xy(100., 100.)).bb()
+    }
+
+    #[test]
+    fn grid_cells_are_squares() {
+        let sites: Vec<Vec2> = (0 .. 100)
+            .map(|i| Vec2::xy((i % 10) as f64 * 10. + 5., (i / 10) as f64 * 10. + 5.))
+            .collect();
+        let cells = voronoi(&sites, region());
+        for cell in &cells {
+            assert!((cell.polygon.area() - 100.).abs() < 1e-9);
+            assert!(cell.polygon.contains(cell.site));
+        }
+        // Corner, edge and inner cells.
+        assert_eq!(cells[0].neighbours.len(), 2);
+        assert_eq!(cells[5].neighbours.len(), 3);
+        assert_eq!(cells[55].neighbours, vec![45, 54, 56, 65]);
+    }
+
+    #[test]
+    fn cells_tile_the_region() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let sites: Vec<Vec2> = (0 .. 500)
+            .map(|_| Vec2::xy(rng.gen_range(0. .. 100.), rng.gen_range(0. .. 100.)))
+            .collect();
+        let cells = voronoi(&sites, region());
+        let area: f64 = cells.iter().map(|cell| cell.polygon.area()).sum();
+        assert!((area - 10_000.).abs() < 1e-6);
+        for (i, cell) in cells.iter().enumerate() {
+            assert!(cell.polygon.contains(cell.site));
+            assert_eq!(cell.polygon.orientation(), Orientation::Positive);
+            for &j in &cell.neighbours {
+                assert!(
+                    cells[j].neighbours.contains(&i),
+                    "{i} and {j} are not mutual neighbours"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn convex_polygon_region() {
+        let triangle = Polygon::from_points(vec![
+            Vec2::xy(0., 0.),
+            Vec2::xy(0., 100.),
+            Vec2::xy(100., 0.),
+        ]);
+        let sites = vec![
+            Vec2::xy(10., 10.),
+            Vec2::xy(30., 10.),
+            Vec2::xy(10., 30.),
+            Vec2::xy(200., 200.),
+        ];
+        let cells = voronoi(&sites, triangle.clone());
+        let area: f64 = cells.iter().map(|cell| cell.polygon.area()).sum();
+        assert!((area - triangle.area()).abs() < 1e-9);
+        assert_eq!(cells[3].polygon.points().count(), 0);
+        assert!(cells[3].neighbours.is_empty());
+    }
+
+    #[test]
+    fn coincident_sites_share_one_cell() {
+        let a = Vec2::xy(25., 50.);
+        let b = Vec2::xy(75., 50.);
+        for sites in [
+            vec![a, a],
+            vec![a, b, a],
+            vec![a, b, a, Vec2::xy(50., 90.), b],
+        ] {
+            let cells = voronoi(&sites, region());
+            let area: f64 = cells.iter().map(|cell| cell.polygon.area()).sum();
+            assert!((area - 10_000.).abs() < 1e-9, "{sites:?}");
+            for (i, cell) in cells.iter().enumerate() {
+                let first = sites.iter().position(|s| *s == sites[i]).unwrap();
+                assert_eq!(cell.polygon.points().count() == 0, first != i);
+                assert!(cell
+                    .neighbours
+                    .iter()
+                    .all(|&j| j != i && cells[j].neighbours.contains(&i)));
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "Voronoi region must be convex")]
+    fn concave_region_is_rejected() {
+        let l_shape = Polygon::from_points(vec![
+            Vec2::xy(0., 0.),
+            Vec2::xy(100., 0.),
+            Vec2::xy(100., 50.),
+            Vec2::xy(50., 50.),
+            Vec2::xy(50., 100.),
+            Vec2::xy(0., 100.),
+        ]);
+        voronoi(&[Vec2::xy(10., 10.)], l_shape);
+    }
+
+    #[test]
+    fn few_sites() {
+        let single = voronoi(&[Vec2::xy(50., 50.)], region());
+        assert!((single[0].polygon.area() - 10_000.).abs() < 1e-9);
+
+        let pair = voronoi(&[Vec2::xy(25., 50.), Vec2::xy(75., 50.)], region());
+        assert!((pair[0].polygon.area() - 5_000.).abs() < 1e-9);
+        assert_eq!(pair[0].neighbours, vec![1]);
+        assert_eq!(pair[1].neighbours, vec![0]);
+    }
+}
@@ -0,0 +1,3 @@
+pub mod draw;
+pub mod geometry;
+pub mod numerics;
@@ -1,13 +1,9 @@
-pub mod draw;
-pub mod geometry;
-pub mod numerics;
-
-use draw::{
+use generustive::draw::{
     color::{mma97::mma97, Rgb},
     digital::{png, *},
 };
 
-use geometry::*;
+use generustive::geometry::*;
 use rand::{rngs::SmallRng, SeedableRng};
 
 fn main() -> Result<(), cairo::IoError> {
@@ -15,7 +11,7 @@ fn main() -> Result<(), cairo::IoError> {
         let mut rng: SmallRng = SmallRng::seed_from_u64(0);
         let points = poisson_disc(
             &mut rng,
-            vec![Vec2::xy(10., 10.), Vec2::xy(width - 10., height - 10.)],
+            (Vec2::xy(10., 10.), Vec2::xy(width - 10., height - 10.)).bb(),
             10.,
             50,
         );
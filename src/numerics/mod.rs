@@ -1 +1,2 @@
 pub mod interpolation;
+pub mod noise;
@@ -0,0 +1,190 @@
+use super::*;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum FractalKind {
+    Fbm,
+    Ridged,
+    Turbulence,
+}
+
+/// Octaves of a noise at increasing frequencies and decreasing amplitudes,
+/// for detail on all scales.
+///
+/// By default, there are 6 octaves, each with twice the frequency (lacunarity)
+/// and half the amplitude (gain) of the previous one.
+pub struct Fractal<S> {
+    source: S,
+    kind: FractalKind,
+    octaves: usize,
+    lacunarity: f64,
+    gain: f64,
+}
+
+/// Offset between octaves, so that the lattices of the octaves do not line up.
+const OCTAVE_SHIFT: f64 = 17.31;
+
+impl<S> Fractal<S> {
+    fn with_kind(source: S, kind: FractalKind) -> Self {
+        Fractal {
+            source,
+            kind,
+            octaves: 6,
+            lacunarity: 2.,
+            gain: 0.5,
+        }
+    }
+
+    /// Fractal Brownian motion: the weighted sum of the octaves. In [-1, 1]
+    /// if the source noise is.
+    pub fn fbm(source: S) -> Self {
+        Self::with_kind(source, FractalKind::Fbm)
+    }
+
+    /// Sharp ridges where the source noise crosses 0, like mountain ranges.
+    /// In [-1, 1] if the source noise is.
+    pub fn ridged(source: S) -> Self {
+        Self::with_kind(source, FractalKind::Ridged)
+    }
+
+    /// Sum of the absolute values of the octaves, with creases where the
+    /// source noise crosses 0, like fire or marble. In [0, 1] if the source
+    /// noise is in [-1, 1].
+    pub fn turbulence(source: S) -> Self {
+        Self::with_kind(source, FractalKind::Turbulence)
+    }
+
+    pub fn octaves(mut self, octaves: usize) -> Self {
+        self.octaves = octaves;
+        self
+    }
+
+    /// Frequency factor between octaves.
+    pub fn lacunarity(mut self, lacunarity: f64) -> Self {
+        self.lacunarity = lacunarity;
+        self
+    }
+
+    /// Amplitude factor between octaves.
+    pub fn gain(mut self, gain: f64) -> Self {
+        self.gain = gain;
+        self
+    }
+}
+
+impl<const N: usize, S: Noise<N>> Noise<N> for Fractal<S> {
+    fn get(&self, point: [f64; N]) -> f64 {
+        let (mut frequency, mut amplitude) = (1., 1.);
+        let (mut sum, mut total_amplitude) = (0., 0.);
+        for octave in 0 .. self.octaves {
+            let shift = octave as f64 * OCTAVE_SHIFT;
+            let value = self.source.get(point.map(|x| x * frequency + shift));
+            sum += amplitude
+                * match self.kind {
+                    FractalKind::Fbm => value,
+                    FractalKind::Ridged => (1. - value.abs()).powi(2),
+                    FractalKind::Turbulence => value.abs(),
+                };
+            total_amplitude += amplitude;
+            frequency *= self.lacunarity;
+            amplitude *= self.gain;
+        }
+        if total_amplitude == 0. {
+            return 0.;
+        }
+        let normalized = sum / total_amplitude;
+        match self.kind {
+            FractalKind::Ridged => 2. * normalized - 1.,
+            FractalKind::Fbm | FractalKind::Turbulence => normalized,
+        }
+    }
+}
+
+/// Domain warping: sample the source noise at points displaced by another
+/// noise, for swirly, marbled patterns.
+///
+/// Each coordinate is displaced by `strength` times the warp noise, which is
+/// sampled at a different offset for each coordinate so that they are
+/// independent.
+pub struct DomainWarp<S, W> {
+    source: S,
+    warp: W,
+    strength: f64,
+}
+
+/// Offset between the samples of the warp noise for different coordinates.
+const WARP_SHIFT: f64 = 31.41;
+
+impl<S, W> DomainWarp<S, W> {
+    pub fn new(source: S, warp: W, strength: f64) -> Self {
+        DomainWarp {
+            source,
+            warp,
+            strength,
+        }
+    }
+}
+
+impl<const N: usize, S: Noise<N>, W: Noise<N>> Noise<N> for DomainWarp<S, W> {
+    fn get(&self, point: [f64; N]) -> f64 {
+        let displacement: [f64; N] = std::array::from_fn(|i| {
+            let shift = (i + 1) as f64 * WARP_SHIFT;
+            self.warp.get(point.map(|x| x + shift))
+        });
+        self.source.get(std::array::from_fn(|i| {
+            point[i] + self.strength * displacement[i]
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::numerics::noise::*;
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+    fn random_points() -> Vec<[f64; 2]> {
+        let mut rng = SmallRng::seed_from_u64(0);
+        (0 .. 1000)
+            .map(|_| std::array::from_fn(|_| rng.gen_range(-10. .. 10.)))
+            .collect()
+    }
+
+    #[test]
+    fn single_octave_is_the_source() {
+        let perlin = Perlin::seeded(0);
+        let fbm = Fractal::fbm(&perlin).octaves(1);
+        let turbulence = Fractal::turbulence(&perlin).octaves(1);
+        for p in random_points() {
+            assert_eq!(fbm.get(p), perlin.get(p));
+            assert_eq!(turbulence.get(p), perlin.get(p).abs());
+        }
+    }
+
+    #[test]
+    fn ranges() {
+        let simplex = Simplex::seeded(1);
+        let fbm = Fractal::fbm(&simplex).octaves(8).gain(0.6);
+        let ridged = Fractal::ridged(&simplex);
+        let turbulence = Fractal::turbulence(&simplex).lacunarity(2.5);
+        for p in random_points() {
+            assert!(fbm.get(p).abs() <= 1.);
+            assert!(ridged.get(p).abs() <= 1.);
+            assert!((0. ..= 1.).contains(&turbulence.get(p)));
+        }
+        assert_eq!(Fractal::fbm(&simplex).octaves(0).get([1., 2.]), 0.);
+    }
+
+    #[test]
+    fn domain_warp() {
+        let source = Perlin::seeded(2);
+        let warp = Simplex::seeded(3);
+        let unwarped = DomainWarp::new(&source, &warp, 0.);
+        let warped = DomainWarp::new(&source, &warp, 4.);
+        let points = random_points();
+        assert!(points.iter().all(|p| unwarped.get(*p) == source.get(*p)));
+        let changed = points
+            .iter()
+            .filter(|p| warped.get(**p) != source.get(**p))
+            .count();
+        assert!(changed > 900);
+    }
+}
@@ -0,0 +1,110 @@
+//! Coherent noise in any number of dimensions.
+//!
+//! All generators are seeded from a random number generator. Only basic
+//! arithmetic and square roots are used, so a seed gives the same noise on
+//! every platform – as long as the generator is portable too, like the ones
+//! from `rand_pcg` and `rand_chacha`, but unlike `SmallRng`.
+
+mod fractal;
+mod perlin;
+mod simplex;
+mod value;
+mod worley;
+
+pub use fractal::*;
+pub use perlin::*;
+pub use simplex::*;
+pub use value::*;
+pub use worley::*;
+
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_pcg::Pcg64;
+
+/// Noise function over N-dimensional space.
+///
+/// 2D noise can be sampled at a [`crate::geometry::Vec2`] with
+/// `noise.get(point.into())`.
+pub trait Noise<const N: usize> {
+    fn get(&self, point: [f64; N]) -> f64;
+}
+
+impl<const N: usize, T: Noise<N> + ?Sized> Noise<N> for &T {
+    fn get(&self, point: [f64; N]) -> f64 {
+        (**self).get(point)
+    }
+}
+
+impl<const N: usize, T: Noise<N> + ?Sized> Noise<N> for Box<T> {
+    fn get(&self, point: [f64; N]) -> f64 {
+        (**self).get(point)
+    }
+}
+
+/// Generator for the given seed, for the `seeded` constructors.
+fn seeded_rng(seed: u64) -> Pcg64 {
+    Pcg64::seed_from_u64(seed)
+}
+
+/// Pseudo-random hashes of lattice points. The lattice repeats every 256
+/// units along each axis.
+struct Permutation([u8; 256]);
+
+impl Permutation {
+    fn new<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let mut table = [0; 256];
+        for (i, entry) in table.iter_mut().enumerate() {
+            *entry = i as u8;
+        }
+        table.shuffle(rng);
+        Permutation(table)
+    }
+
+    fn hash<const N: usize>(&self, lattice: [i64; N]) -> usize {
+        lattice
+            .iter()
+            .fold(0, |hash, &x| self.mix(hash, (x & 255) as usize))
+    }
+
+    /// Derive a new hash from a hash and a number.
+    fn mix(&self, hash: usize, x: usize) -> usize {
+        self.0[(hash + x) & 255] as usize
+    }
+}
+
+/// Dot product of a pseudo-random gradient with the offset from its lattice
+/// point.
+///
+/// Like Perlin’s improved noise, the gradients point to the middles of the
+/// edges of the hypercube, so that they are cheap to evaluate: all coordinates
+/// are ±1, except one which is 0. In 1D and 2D they point to the corners
+/// instead.
+fn gradient_dot<const N: usize>(hash: usize, offset: [f64; N]) -> f64 {
+    let (zero, mut signs) = if N <= 2 {
+        (N, hash)
+    } else {
+        (hash % N, hash / N)
+    };
+    let mut sum = 0.;
+    for (i, d) in offset.into_iter().enumerate() {
+        if i != zero {
+            sum += if signs & 1 == 0 { d } else { -d };
+        }
+        signs >>= 1;
+    }
+    sum
+}
+
+/// Smooth step with vanishing first and second derivatives at 0 and 1.
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6. - 15.) + 10.)
+}
+
+/// Integer part and fractional part of each coordinate.
+fn split<const N: usize>(point: [f64; N]) -> ([i64; N], [f64; N]) {
+    let floor = point.map(f64::floor);
+    (
+        floor.map(|x| x as i64),
+        std::array::from_fn(|i| point[i] - floor[i]),
+    )
+}
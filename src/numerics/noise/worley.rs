@@ -0,0 +1,171 @@
+use super::*;
+
+/// Which distance [`Worley`] noise yields.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum WorleyDistance {
+    /// Distance to the nearest feature point, for round cells.
+    Nearest,
+    /// Distance to the second nearest feature point.
+    SecondNearest,
+    /// Difference of the two, which is 0 on the borders between cells.
+    Difference,
+}
+
+/// Worley’s cellular noise (1996): the distance to scattered feature points,
+/// one per unit cube.
+///
+/// The result is a distance in units of the lattice, so it is at least 0 and
+/// mostly below 1, but not normalized.
+pub struct Worley {
+    permutation: Permutation,
+    jitter: [f64; 256],
+    distance: WorleyDistance,
+}
+
+impl Worley {
+    pub fn new<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let permutation = Permutation::new(rng);
+        let jitter = std::array::from_fn(|_| rng.gen());
+        Worley {
+            permutation,
+            jitter,
+            distance: WorleyDistance::Nearest,
+        }
+    }
+
+    pub fn seeded(seed: u64) -> Self {
+        Self::new(&mut seeded_rng(seed))
+    }
+
+    pub fn distance(mut self, distance: WorleyDistance) -> Self {
+        self.distance = distance;
+        self
+    }
+
+    /// Feature point of a lattice cell.
+    fn feature_point<const N: usize>(&self, lattice: [i64; N]) -> [f64; N] {
+        let hash = self.permutation.hash(lattice);
+        std::array::from_fn(|i| lattice[i] as f64 + self.jitter[self.permutation.mix(hash, i)])
+    }
+}
+
+impl<const N: usize> Noise<N> for Worley {
+    fn get(&self, point: [f64; N]) -> f64 {
+        let (cell, _) = split(point);
+        let (mut nearest, mut second) = (f64::INFINITY, f64::INFINITY);
+
+        // Search cubic shells of cells around the point’s cell. Every cell of
+        // the shell at Chebyshev distance `ring` is more than `ring - 1` away,
+        // so the search can stop once that exceeds the wanted distance.
+        for ring in 0_i64 .. {
+            let wanted = match self.distance {
+                WorleyDistance::Nearest => nearest,
+                WorleyDistance::SecondNearest | WorleyDistance::Difference => second,
+            };
+            let gap = (ring - 1).max(0) as f64;
+            if gap * gap >= wanted {
+                break;
+            }
+            let side = 2 * ring + 1;
+            for neighbour in 0 .. side.pow(N as u32) {
+                let mut offset = [0; N];
+                let mut digits = neighbour;
+                for coordinate in &mut offset {
+                    *coordinate = digits % side - ring;
+                    digits /= side;
+                }
+                if offset.iter().all(|o| o.abs() != ring) {
+                    continue;
+                }
+                let lattice: [i64; N] = std::array::from_fn(|i| cell[i] + offset[i]);
+                // Skip cells whose feature point cannot be close enough.
+                let cell_distance: f64 = (0 .. N)
+                    .map(|i| {
+                        let low = lattice[i] as f64;
+                        (low - point[i]).max(point[i] - (low + 1.)).max(0.).powi(2)
+                    })
+                    .sum();
+                if cell_distance >= second {
+                    continue;
+                }
+                let feature = self.feature_point(lattice);
+                let distance: f64 = (0 .. N).map(|i| (point[i] - feature[i]).powi(2)).sum();
+                if distance < nearest {
+                    (nearest, second) = (distance, nearest);
+                } else if distance < second {
+                    second = distance;
+                }
+            }
+        }
+
+        match self.distance {
+            WorleyDistance::Nearest => nearest.sqrt(),
+            WorleyDistance::SecondNearest => second.sqrt(),
+            WorleyDistance::Difference => second.sqrt() - nearest.sqrt(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::numerics::noise::*;
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+    #[test]
+    fn distances_are_consistent() {
+        let nearest = Worley::seeded(0);
+        let second = Worley::seeded(0).distance(WorleyDistance::SecondNearest);
+        let difference = Worley::seeded(0).distance(WorleyDistance::Difference);
+        let mut rng = SmallRng::seed_from_u64(0);
+        for _ in 0 .. 1000 {
+            let p: [f64; 2] = std::array::from_fn(|_| rng.gen_range(-20. .. 20.));
+            let (f1, f2) = (nearest.get(p), second.get(p));
+            assert!(0. <= f1 && f1 <= f2);
+            // One feature point per cell, and a cell is at most √2 across.
+            assert!(f1 < 2_f64.sqrt());
+            assert!((difference.get(p) - (f2 - f1)).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn every_cell_has_a_feature_point() {
+        // Sampling a cell densely finds a point close to its feature point.
+        let noise = Worley::seeded(1);
+        for (cx, cy) in [(0., 0.), (-3., 5.), (10., -10.)] {
+            let closest = (0 .. 100 * 100)
+                .map(|i| noise.get([cx + (i % 100) as f64 / 100., cy + (i / 100) as f64 / 100.]))
+                .fold(f64::INFINITY, f64::min);
+            assert!(closest < 0.01);
+        }
+    }
+
+    #[test]
+    fn all_distances_are_continuous() {
+        // Distances to the nearest points change at most as fast as the
+        // point moves, also across cell borders.
+        let step = 1e-3;
+        for distance in [
+            WorleyDistance::Nearest,
+            WorleyDistance::SecondNearest,
+            WorleyDistance::Difference,
+        ] {
+            for seed in 0 .. 2 {
+                let noise = Worley::seeded(seed).distance(distance);
+                for row in 0 .. 10 {
+                    let y = 2. * row as f64 + 0.37;
+                    let values: Vec<f64> = (0 .. 20_000)
+                        .map(|i| noise.get([i as f64 * step, y]))
+                        .collect();
+                    // Difference of two 1-Lipschitz functions.
+                    let limit = match distance {
+                        WorleyDistance::Difference => 2. * step,
+                        _ => step,
+                    };
+                    for pair in values.windows(2) {
+                        assert!((pair[1] - pair[0]).abs() <= limit + 1e-9, "{distance:?}");
+                    }
+                }
+            }
+        }
+    }
+}